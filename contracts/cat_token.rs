@@ -1,219 +0,0 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
-
-declare_id!("CATCoinTokenProgram11111111111111111111111");
-
-#[program]
-pub mod cat_token {
-    use super::*;
-
-    /// Initialize the CAT COIN token mint
-    pub fn initialize_token(
-        ctx: Context<InitializeToken>,
-        decimals: u8,
-        name: String,
-        symbol: String,
-    ) -> Result<()> {
-        let token_info = &mut ctx.accounts.token_info;
-        token_info.authority = ctx.accounts.authority.key();
-        token_info.mint = ctx.accounts.mint.key();
-        token_info.name = name;
-        token_info.symbol = symbol;
-        token_info.decimals = decimals;
-        token_info.total_supply = 0;
-        token_info.max_supply = 1_000_000_000_000_000; // 1 Quadrillion CAT tokens (with 6 decimals)
-        
-        msg!("CAT COIN Token initialized successfully!");
-        msg!("Name: {}", token_info.name);
-        msg!("Symbol: {}", token_info.symbol);
-        msg!("Decimals: {}", token_info.decimals);
-        msg!("Max Supply: {}", token_info.max_supply);
-        
-        Ok(())
-    }
-
-    /// Mint new CAT tokens (only by authority)
-    pub fn mint_tokens(
-        ctx: Context<MintTokens>,
-        amount: u64,
-    ) -> Result<()> {
-        let token_info = &mut ctx.accounts.token_info;
-        
-        // Check if minting would exceed max supply
-        require!(
-            token_info.total_supply + amount <= token_info.max_supply,
-            ErrorCode::ExceedsMaxSupply
-        );
-
-        // Mint tokens
-        let cpi_accounts = token::MintTo {
-            mint: ctx.accounts.mint.to_account_info(),
-            to: ctx.accounts.to.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        
-        token::mint_to(cpi_ctx, amount)?;
-        
-        // Update total supply
-        token_info.total_supply += amount;
-        
-        msg!("Minted {} CAT tokens", amount);
-        msg!("Total supply: {}", token_info.total_supply);
-        
-        Ok(())
-    }
-
-    /// Transfer CAT tokens between accounts
-    pub fn transfer_tokens(
-        ctx: Context<TransferTokens>,
-        amount: u64,
-    ) -> Result<()> {
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.from.to_account_info(),
-            to: ctx.accounts.to.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        
-        token::transfer(cpi_ctx, amount)?;
-        
-        msg!("Transferred {} CAT tokens", amount);
-        
-        Ok(())
-    }
-
-    /// Burn CAT tokens
-    pub fn burn_tokens(
-        ctx: Context<BurnTokens>,
-        amount: u64,
-    ) -> Result<()> {
-        let token_info = &mut ctx.accounts.token_info;
-        
-        let cpi_accounts = token::Burn {
-            mint: ctx.accounts.mint.to_account_info(),
-            from: ctx.accounts.from.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        
-        token::burn(cpi_ctx, amount)?;
-        
-        // Update total supply
-        token_info.total_supply -= amount;
-        
-        msg!("Burned {} CAT tokens", amount);
-        msg!("Total supply: {}", token_info.total_supply);
-        
-        Ok(())
-    }
-}
-
-#[derive(Accounts)]
-pub struct InitializeToken<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + TokenInfo::INIT_SPACE,
-        seeds = [b"token_info"],
-        bump
-    )]
-    pub token_info: Account<'info, TokenInfo>,
-    
-    #[account(
-        init,
-        payer = authority,
-        mint::decimals = 6,
-        mint::authority = authority,
-    )]
-    pub mint: Account<'info, Mint>,
-    
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
-
-#[derive(Accounts)]
-pub struct MintTokens<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"token_info"],
-        bump,
-        has_one = authority,
-        has_one = mint
-    )]
-    pub token_info: Account<'info, TokenInfo>,
-    
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct TransferTokens<'info> {
-    pub authority: Signer<'info>,
-    
-    #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct BurnTokens<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"token_info"],
-        bump,
-        has_one = authority,
-        has_one = mint
-    )]
-    pub token_info: Account<'info, TokenInfo>,
-    
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
-}
-
-#[account]
-#[derive(InitSpace)]
-pub struct TokenInfo {
-    pub authority: Pubkey,
-    pub mint: Pubkey,
-    #[max_len(32)]
-    pub name: String,
-    #[max_len(10)]
-    pub symbol: String,
-    pub decimals: u8,
-    pub total_supply: u64,
-    pub max_supply: u64,
-}
-
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Minting would exceed maximum supply")]
-    ExceedsMaxSupply,
-}
\ No newline at end of file
@@ -0,0 +1,269 @@
+//! Instruction builders for `cat_airdrop`.
+//!
+//! Campaigns are addressed by their `creator` (the initializing authority) and
+//! `campaign_id`; the `airdrop_info`, vault, fee vault and claim record PDAs
+//! are derived from them.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{system_program, sysvar};
+use cat_coin_contracts::airdrop::{accounts, instruction as args, AirdropUpdate};
+
+use crate::{instruction, pda, AIRDROP_PROGRAM_ID};
+
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_airdrop(
+    authority: Pubkey,
+    token_mint: Pubkey,
+    campaign_id: u64,
+    total_airdrop_amount: u64,
+    merkle_root: [u8; 32],
+    start_time: i64,
+    end_time: i64,
+    vesting_cliff: i64,
+    vesting_duration: i64,
+    claim_fee_lamports: u64,
+    attestor: Pubkey, // Default = attested claims disabled
+) -> Instruction {
+    let airdrop_info = pda::airdrop_info(&authority, campaign_id).0;
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        accounts::InitializeAirdrop {
+            authority,
+            airdrop_info,
+            token_mint,
+            token_vault: pda::airdrop_token_vault(&airdrop_info).0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        args::InitializeAirdrop {
+            campaign_id,
+            total_airdrop_amount,
+            merkle_root,
+            start_time,
+            end_time,
+            vesting_cliff,
+            vesting_duration,
+            claim_fee_lamports,
+            attestor,
+        },
+    )
+}
+
+/// Claim with a Merkle proof, as written by `cat-airdrop-merkle build`
+pub fn claim_airdrop(
+    creator: Pubkey,
+    campaign_id: u64,
+    user: Pubkey,
+    user_token_account: Pubkey,
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        claim_accounts(creator, campaign_id, user, user_token_account),
+        args::ClaimAirdrop { index, amount, proof },
+    )
+}
+
+/// Claim with a backend attestation. The transaction must carry the ed25519
+/// verify instruction over `airdrop::attestation_message` right before this one.
+pub fn claim_attested(
+    creator: Pubkey,
+    campaign_id: u64,
+    user: Pubkey,
+    user_token_account: Pubkey,
+    amount: u64,
+    expiry: i64,
+) -> Instruction {
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        accounts::ClaimAttested {
+            claim: claim_accounts(creator, campaign_id, user, user_token_account),
+            instructions: sysvar::instructions::ID,
+        },
+        args::ClaimAttested { amount, expiry },
+    )
+}
+
+pub fn withdraw_vested(
+    creator: Pubkey,
+    campaign_id: u64,
+    user: Pubkey,
+    user_token_account: Pubkey,
+) -> Instruction {
+    let airdrop_info = pda::airdrop_info(&creator, campaign_id).0;
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        accounts::WithdrawVested {
+            user,
+            airdrop_info,
+            claim_record: pda::claim_record(&airdrop_info, &user).0,
+            token_vault: pda::airdrop_token_vault(&airdrop_info).0,
+            user_token_account,
+            token_program: anchor_spl::token::ID,
+        },
+        args::WithdrawVested {},
+    )
+}
+
+pub fn fund_airdrop(
+    creator: Pubkey,
+    campaign_id: u64,
+    authority: Pubkey,
+    authority_token_account: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let airdrop_info = pda::airdrop_info(&creator, campaign_id).0;
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        accounts::FundAirdrop {
+            authority,
+            airdrop_info,
+            authority_token_account,
+            token_vault: pda::airdrop_token_vault(&airdrop_info).0,
+            token_program: anchor_spl::token::ID,
+        },
+        args::FundAirdrop { amount },
+    )
+}
+
+pub fn queue_airdrop_update(
+    creator: Pubkey,
+    campaign_id: u64,
+    authority: Pubkey,
+    update: AirdropUpdate,
+) -> Instruction {
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        update_accounts(creator, campaign_id, authority),
+        args::QueueAirdropUpdate { update },
+    )
+}
+
+pub fn execute_airdrop_update(creator: Pubkey, campaign_id: u64, authority: Pubkey) -> Instruction {
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        update_accounts(creator, campaign_id, authority),
+        args::ExecuteAirdropUpdate {},
+    )
+}
+
+pub fn cancel_airdrop_update(creator: Pubkey, campaign_id: u64, authority: Pubkey) -> Instruction {
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        update_accounts(creator, campaign_id, authority),
+        args::CancelAirdropUpdate {},
+    )
+}
+
+pub fn pause_airdrop(creator: Pubkey, campaign_id: u64, authority: Pubkey) -> Instruction {
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        update_accounts(creator, campaign_id, authority),
+        args::PauseAirdrop {},
+    )
+}
+
+pub fn propose_authority(
+    creator: Pubkey,
+    campaign_id: u64,
+    authority: Pubkey,
+    new_authority: Pubkey,
+) -> Instruction {
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        update_accounts(creator, campaign_id, authority),
+        args::ProposeAuthority { new_authority },
+    )
+}
+
+pub fn accept_authority(creator: Pubkey, campaign_id: u64, new_authority: Pubkey) -> Instruction {
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        accounts::AcceptAirdropAuthority {
+            new_authority,
+            airdrop_info: pda::airdrop_info(&creator, campaign_id).0,
+        },
+        args::AcceptAuthority {},
+    )
+}
+
+pub fn cancel_authority_transfer(
+    creator: Pubkey,
+    campaign_id: u64,
+    authority: Pubkey,
+) -> Instruction {
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        update_accounts(creator, campaign_id, authority),
+        args::CancelAuthorityTransfer {},
+    )
+}
+
+pub fn withdraw_fees(creator: Pubkey, campaign_id: u64, authority: Pubkey) -> Instruction {
+    let airdrop_info = pda::airdrop_info(&creator, campaign_id).0;
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        accounts::WithdrawFees {
+            authority,
+            airdrop_info,
+            fee_vault: pda::fee_vault(&airdrop_info).0,
+            system_program: system_program::ID,
+        },
+        args::WithdrawFees {},
+    )
+}
+
+pub fn withdraw_remaining(
+    creator: Pubkey,
+    campaign_id: u64,
+    authority: Pubkey,
+    authority_token_account: Pubkey,
+) -> Instruction {
+    let airdrop_info = pda::airdrop_info(&creator, campaign_id).0;
+    instruction(
+        AIRDROP_PROGRAM_ID,
+        accounts::WithdrawRemaining {
+            authority,
+            airdrop_info,
+            authority_token_account,
+            token_vault: pda::airdrop_token_vault(&airdrop_info).0,
+            token_program: anchor_spl::token::ID,
+        },
+        args::WithdrawRemaining {},
+    )
+}
+
+fn claim_accounts(
+    creator: Pubkey,
+    campaign_id: u64,
+    user: Pubkey,
+    user_token_account: Pubkey,
+) -> accounts::ClaimAirdrop {
+    let airdrop_info = pda::airdrop_info(&creator, campaign_id).0;
+    accounts::ClaimAirdrop {
+        user,
+        airdrop_info,
+        claim_record: pda::claim_record(&airdrop_info, &user).0,
+        token_vault: pda::airdrop_token_vault(&airdrop_info).0,
+        fee_vault: pda::fee_vault(&airdrop_info).0,
+        user_token_account,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    }
+}
+
+fn update_accounts(
+    creator: Pubkey,
+    campaign_id: u64,
+    authority: Pubkey,
+) -> accounts::UpdateAirdrop {
+    accounts::UpdateAirdrop {
+        authority,
+        airdrop_info: pda::airdrop_info(&creator, campaign_id).0,
+    }
+}
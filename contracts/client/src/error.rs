@@ -0,0 +1,180 @@
+//! Decoding of the programs' custom error codes (`Custom(6000 + n)`)
+
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use cat_coin_contracts::{airdrop, cat_token, presale};
+
+use crate::{AIRDROP_PROGRAM_ID, PRESALE_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+/// A custom error returned by one of the CAT programs
+#[derive(Debug, Clone, Copy)]
+pub enum CatError {
+    Token(cat_token::ErrorCode),
+    Airdrop(airdrop::ErrorCode),
+    Presale(presale::ErrorCode),
+}
+
+impl CatError {
+    pub fn code(&self) -> u32 {
+        match self {
+            CatError::Token(error) => (*error).into(),
+            CatError::Airdrop(error) => (*error).into(),
+            CatError::Presale(error) => (*error).into(),
+        }
+    }
+
+    /// Variant name, e.g. `SlippageExceeded`
+    pub fn name(&self) -> String {
+        match self {
+            CatError::Token(error) => error.name(),
+            CatError::Airdrop(error) => error.name(),
+            CatError::Presale(error) => error.name(),
+        }
+    }
+}
+
+impl fmt::Display for CatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatError::Token(error) => error.fmt(f),
+            CatError::Airdrop(error) => error.fmt(f),
+            CatError::Presale(error) => error.fmt(f),
+        }
+    }
+}
+
+/// Map the custom error `code` of a failed instruction to the error of the program
+/// that returned it. Anchor framework errors and unknown programs give `None`.
+pub fn decode_error(program_id: &Pubkey, code: u32) -> Option<CatError> {
+    if *program_id == TOKEN_PROGRAM_ID {
+        token_error(code).map(CatError::Token)
+    } else if *program_id == AIRDROP_PROGRAM_ID {
+        airdrop_error(code).map(CatError::Airdrop)
+    } else if *program_id == PRESALE_PROGRAM_ID {
+        presale_error(code).map(CatError::Presale)
+    } else {
+        None
+    }
+}
+
+/// Reverse lookup over every variant of a program's `ErrorCode`. The match is
+/// exhaustive, so a new program error fails to compile until it is listed.
+macro_rules! error_lookup {
+    ($name:ident, $program:ident, [$($variant:ident),* $(,)?]) => {
+        fn $name(code: u32) -> Option<$program::ErrorCode> {
+            use $program::ErrorCode;
+
+            fn _exhaustive(error: ErrorCode) {
+                match error {
+                    $(ErrorCode::$variant)|* => {}
+                }
+            }
+
+            [$(ErrorCode::$variant),*]
+                .into_iter()
+                .find(|error| u32::from(*error) == code)
+        }
+    };
+}
+
+error_lookup!(token_error, cat_token, [
+    ExceedsMaxSupply,
+    InvalidNewAuthority,
+    NoPendingAuthority,
+    NotPendingAuthority,
+]);
+
+error_lookup!(airdrop_error, airdrop, [
+    AirdropNotActive,
+    AirdropNotStarted,
+    AirdropEnded,
+    AlreadyClaimed,
+    InsufficientTokens,
+    AirdropStillActive,
+    InvalidProof,
+    InvalidVestingSchedule,
+    NothingToWithdraw,
+    ClaimFeeBelowRentExemption,
+    NoFeesToWithdraw,
+    AttestationDisabled,
+    AttestationExpired,
+    MissingAttestation,
+    InvalidAttestation,
+    InvalidNewAuthority,
+    NoPendingAuthority,
+    NotPendingAuthority,
+    UpdateAlreadyQueued,
+    NoUpdateQueued,
+    TimelockNotExpired,
+    InvalidTimelockDelay,
+    MathOverflow,
+]);
+
+error_lookup!(presale_error, presale, [
+    PresaleNotActive,
+    PresaleNotStarted,
+    PresaleEnded,
+    BelowMinimumPurchase,
+    ExceedsMaximumPurchase,
+    InsufficientTokensForSale,
+    InsufficientSOL,
+    InsufficientSOLInVault,
+    PresaleStillActive,
+    InvalidTokenPrice,
+    MathOverflow,
+    NoActiveRound,
+    RoundCapExceeded,
+    TooManyRounds,
+    InvalidRound,
+    PaymentMintNotAccepted,
+    PaymentMintAlreadyAccepted,
+    TooManyPaymentMints,
+    InsufficientPaymentTokensInVault,
+    PresaleAlreadyFinalized,
+    PresaleNotFailed,
+    PresaleNotSucceeded,
+    AlreadyRefunded,
+    MissingRefundAccounts,
+    InvalidCaps,
+    SaleAlreadyStarted,
+    DeferredDeliveryDisabled,
+    TgeAlreadyPassed, // Retired, no longer returned by the program
+    InvalidTgeSchedule,
+    TgeNotReached,
+    NothingToClaim,
+    SlippageExceeded,
+    PriceAboveMaximum,
+    FairLaunchDisabled,
+    FairLaunchDepositsOnly,
+    NothingToSettle,
+    DutchAuctionDisabled,
+    DutchAuctionSolOnly,
+    DutchAuctionNoRounds,
+    DutchAuctionNoFairLaunch,
+    InvalidAuctionConfig,
+    AuctionNotSettled,
+    AuctionAlreadySettled,
+    TgeNotUsed,
+    InvalidVestingSchedule,
+    VaultBelowObligations,
+    NotWhitelisted,
+    InvalidPayees,
+    MissingPayeeAccounts,
+    NothingToDistribute,
+    InvalidReferralBps,
+    ReferrerHasNoPurchase,
+    InvalidReferrer,
+    InvalidNewAuthority,
+    NoPendingAuthority,
+    NotPendingAuthority,
+    UpdateAlreadyQueued,
+    NoUpdateQueued,
+    TimelockNotExpired,
+    InvalidTimelockDelay,
+    ProceedsGoToPayees,
+    RoundsSolOnly,
+    HardCapExceeded,
+    TgeNotScheduled,
+    PresaleNotFinalized,
+]);
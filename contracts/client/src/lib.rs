@@ -0,0 +1,43 @@
+//! Rust client for the CAT COIN programs.
+//!
+//! Builds instructions for `cat_token`, `cat_airdrop` and `cat_presale`,
+//! derives their PDAs, deserializes their accounts and decodes their error
+//! codes. Instruction data and account lists come from the Anchor client
+//! types generated for the programs themselves, so this crate is released in
+//! lockstep with `cat-coin-contracts`.
+
+pub mod airdrop;
+pub mod error;
+pub mod pda;
+pub mod presale;
+pub mod state;
+pub mod token;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+pub use anchor_lang::solana_program::instruction::AccountMeta;
+pub use cat_coin_contracts;
+
+pub use crate::error::{decode_error, CatError};
+
+/// `cat_token` program ID
+pub const TOKEN_PROGRAM_ID: Pubkey = cat_coin_contracts::cat_token::ID;
+/// `cat_airdrop` program ID
+pub const AIRDROP_PROGRAM_ID: Pubkey = cat_coin_contracts::airdrop::ID;
+/// `cat_presale` program ID
+pub const PRESALE_PROGRAM_ID: Pubkey = cat_coin_contracts::presale::ID;
+
+/// Assemble an instruction from Anchor's generated accounts and argument structs
+fn instruction(
+    program_id: Pubkey,
+    accounts: impl ToAccountMetas,
+    args: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
@@ -0,0 +1,68 @@
+//! Program derived addresses, with the same seeds the programs check.
+//!
+//! Every helper returns `(address, bump)` like `Pubkey::find_program_address`.
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::{AIRDROP_PROGRAM_ID, PRESALE_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+/// `TokenInfo` of the CAT mint
+pub fn token_info() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"token_info"], &TOKEN_PROGRAM_ID)
+}
+
+/// `AirdropInfo` of the campaign `campaign_id` initialized by `creator`
+pub fn airdrop_info(creator: &Pubkey, campaign_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"airdrop_info", creator.as_ref(), campaign_id.to_le_bytes().as_ref()],
+        &AIRDROP_PROGRAM_ID,
+    )
+}
+
+/// CAT vault of an airdrop campaign
+pub fn airdrop_token_vault(airdrop_info: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"token_vault", airdrop_info.as_ref()], &AIRDROP_PROGRAM_ID)
+}
+
+/// Lamport vault collecting the claim fees of an airdrop campaign
+pub fn fee_vault(airdrop_info: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_vault", airdrop_info.as_ref()], &AIRDROP_PROGRAM_ID)
+}
+
+/// `ClaimRecord` of `user` in an airdrop campaign
+pub fn claim_record(airdrop_info: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"claim_record", airdrop_info.as_ref(), user.as_ref()],
+        &AIRDROP_PROGRAM_ID,
+    )
+}
+
+/// The single `PresaleInfo`
+pub fn presale_info() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"presale_info"], &PRESALE_PROGRAM_ID)
+}
+
+/// CAT vault of the presale
+pub fn presale_token_vault() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"token_vault"], &PRESALE_PROGRAM_ID)
+}
+
+/// `SolVault` holding the SOL raised
+pub fn sol_vault() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"sol_vault"], &PRESALE_PROGRAM_ID)
+}
+
+/// `PurchaseRecord` of `buyer`
+pub fn purchase_record(buyer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"purchase_record", buyer.as_ref()], &PRESALE_PROGRAM_ID)
+}
+
+/// Vault holding the presale payments made in `payment_mint`
+pub fn payment_vault(payment_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"payment_vault", payment_mint.as_ref()], &PRESALE_PROGRAM_ID)
+}
+
+/// `ReferralRecord` of `referrer`
+pub fn referral_record(referrer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"referral", referrer.as_ref()], &PRESALE_PROGRAM_ID)
+}
@@ -0,0 +1,534 @@
+//! Instruction builders for `cat_presale`.
+//!
+//! The presale is a singleton, so only wallets, token accounts and payment
+//! mints are passed in; every PDA is derived here.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::{system_program, sysvar};
+use cat_coin_contracts::presale::{accounts, instruction as args, PayeeShare, PresaleUpdate, WhitelistProof};
+
+use crate::{instruction, pda, PRESALE_PROGRAM_ID};
+
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_presale(
+    authority: Pubkey,
+    token_mint: Pubkey,
+    token_price: u64,
+    total_tokens_for_sale: u64,
+    min_purchase: u64,
+    max_purchase: u64,
+    start_time: i64,
+    end_time: i64,
+    soft_cap: u64,
+    hard_cap_lamports: u64,
+) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::InitializePresale {
+            authority,
+            presale_info: pda::presale_info().0,
+            token_mint,
+            token_vault: pda::presale_token_vault().0,
+            sol_vault: pda::sol_vault().0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        args::InitializePresale {
+            token_price,
+            total_tokens_for_sale,
+            min_purchase,
+            max_purchase,
+            start_time,
+            end_time,
+            soft_cap,
+            hard_cap_lamports,
+        },
+    )
+}
+
+/// Buy with SOL. Pass `referrer` to credit a registered referrer's `ReferralRecord`.
+/// The filled CAT amount comes back as return data, see `state::return_data`.
+#[allow(clippy::too_many_arguments)]
+pub fn buy_tokens(
+    buyer: Pubkey,
+    buyer_token_account: Pubkey,
+    referrer: Option<Pubkey>,
+    sol_amount: u64,
+    min_tokens_out: u64,
+    max_price: Option<u64>,
+    whitelist_proof: Option<WhitelistProof>,
+) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::BuyTokens {
+            buyer,
+            presale_info: pda::presale_info().0,
+            purchase_record: pda::purchase_record(&buyer).0,
+            token_vault: pda::presale_token_vault().0,
+            sol_vault: pda::sol_vault().0,
+            buyer_token_account,
+            referral_record: referrer.map(|referrer| pda::referral_record(&referrer).0),
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        args::BuyTokens {
+            sol_amount,
+            min_tokens_out,
+            max_price,
+            whitelist_proof,
+        },
+    )
+}
+
+pub fn buy_tokens_with_token(
+    buyer: Pubkey,
+    payment_mint: Pubkey,
+    buyer_payment_account: Pubkey,
+    buyer_token_account: Pubkey,
+    payment_amount: u64,
+) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::BuyTokensWithToken {
+            buyer,
+            presale_info: pda::presale_info().0,
+            purchase_record: pda::purchase_record(&buyer).0,
+            token_vault: pda::presale_token_vault().0,
+            payment_mint,
+            payment_vault: pda::payment_vault(&payment_mint).0,
+            buyer_payment_account,
+            buyer_token_account,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        args::BuyTokensWithToken { payment_amount },
+    )
+}
+
+/// Simulate to read the CAT amount `buy_tokens` would fill from the return data
+pub fn quote_purchase(sol_amount: u64) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::QuotePurchase {
+            presale_info: pda::presale_info().0,
+        },
+        args::QuotePurchase { sol_amount },
+    )
+}
+
+/// Simulate to read the `SolVaultBalance` from the return data
+pub fn get_sol_vault_balance() -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::GetSolVaultBalance {
+            presale_info: pda::presale_info().0,
+            sol_vault: pda::sol_vault().0,
+        },
+        args::GetSolVaultBalance {},
+    )
+}
+
+pub fn register_referrer(referrer: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::RegisterReferrer {
+            referrer,
+            purchase_record: pda::purchase_record(&referrer).0,
+            referral_record: pda::referral_record(&referrer).0,
+            system_program: system_program::ID,
+        },
+        args::RegisterReferrer {},
+    )
+}
+
+pub fn claim_referral_rewards(referrer: Pubkey, referrer_token_account: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::ClaimReferralRewards {
+            referrer,
+            presale_info: pda::presale_info().0,
+            referral_record: pda::referral_record(&referrer).0,
+            token_vault: pda::presale_token_vault().0,
+            referrer_token_account,
+            token_program: anchor_spl::token::ID,
+        },
+        args::ClaimReferralRewards {},
+    )
+}
+
+pub fn deposit_sol(buyer: Pubkey, amount: u64) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::DepositSol {
+            buyer,
+            presale_info: pda::presale_info().0,
+            purchase_record: pda::purchase_record(&buyer).0,
+            sol_vault: pda::sol_vault().0,
+            system_program: system_program::ID,
+        },
+        args::DepositSol { amount },
+    )
+}
+
+/// Settle `depositor`'s fair launch deposit; `payer` can be anyone
+pub fn settle_deposit(payer: Pubkey, depositor: Pubkey, depositor_token_account: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::SettleDeposit {
+            payer,
+            depositor,
+            presale_info: pda::presale_info().0,
+            purchase_record: pda::purchase_record(&depositor).0,
+            token_vault: pda::presale_token_vault().0,
+            sol_vault: pda::sol_vault().0,
+            depositor_token_account,
+            token_program: anchor_spl::token::ID,
+        },
+        args::SettleDeposit {},
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_round(
+    authority: Pubkey,
+    token_price: u64,
+    token_cap: u64,
+    min_purchase: u64,
+    max_purchase: u64,
+    start_time: i64,
+    end_time: i64,
+) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::AddRound {
+            token_price,
+            token_cap,
+            min_purchase,
+            max_purchase,
+            start_time,
+            end_time,
+        },
+    )
+}
+
+pub fn set_referral_bps(authority: Pubkey, referral_bps: u16) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::SetReferralBps { referral_bps },
+    )
+}
+
+pub fn set_round_vesting(
+    authority: Pubkey,
+    round_index: u8,
+    tge_unlock_bps: u16,
+    vesting_cliff: i64,
+    vesting_duration: i64,
+) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::SetRoundVesting {
+            round_index,
+            tge_unlock_bps,
+            vesting_cliff,
+            vesting_duration,
+        },
+    )
+}
+
+pub fn set_round_whitelist(authority: Pubkey, round_index: u8, merkle_root: [u8; 32]) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::SetRoundWhitelist { round_index, merkle_root },
+    )
+}
+
+pub fn configure_dutch_auction(
+    authority: Pubkey,
+    start_price: u64,
+    floor_price: u64,
+    step_interval: i64,
+) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::ConfigureDutchAuction {
+            start_price,
+            floor_price,
+            step_interval,
+        },
+    )
+}
+
+pub fn set_tge(authority: Pubkey, tge_time: i64, tge_unlock_bps: u16, vesting_duration: i64) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::SetTge {
+            tge_time,
+            tge_unlock_bps,
+            vesting_duration,
+        },
+    )
+}
+
+pub fn set_payees(authority: Pubkey, payees: Vec<PayeeShare>) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::SetPayees { payees },
+    )
+}
+
+pub fn queue_presale_update(authority: Pubkey, update: PresaleUpdate) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::QueuePresaleUpdate { update },
+    )
+}
+
+pub fn execute_presale_update(authority: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::ExecutePresaleUpdate {},
+    )
+}
+
+pub fn cancel_presale_update(authority: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::CancelPresaleUpdate {},
+    )
+}
+
+pub fn pause_presale(authority: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::PausePresale {},
+    )
+}
+
+pub fn propose_authority(authority: Pubkey, new_authority: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::ProposeAuthority { new_authority },
+    )
+}
+
+pub fn accept_authority(new_authority: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::AcceptPresaleAuthority {
+            new_authority,
+            presale_info: pda::presale_info().0,
+        },
+        args::AcceptAuthority {},
+    )
+}
+
+pub fn cancel_authority_transfer(authority: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        update_accounts(authority),
+        args::CancelAuthorityTransfer {},
+    )
+}
+
+pub fn add_payment_mint(authority: Pubkey, payment_mint: Pubkey, token_price: u64) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::AddPaymentMint {
+            authority,
+            presale_info: pda::presale_info().0,
+            payment_mint,
+            payment_vault: pda::payment_vault(&payment_mint).0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        args::AddPaymentMint { token_price },
+    )
+}
+
+pub fn fund_presale(authority: Pubkey, authority_token_account: Pubkey, amount: u64) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::FundPresale {
+            authority,
+            presale_info: pda::presale_info().0,
+            authority_token_account,
+            token_vault: pda::presale_token_vault().0,
+            token_program: anchor_spl::token::ID,
+        },
+        args::FundPresale { amount },
+    )
+}
+
+/// Permissionless
+pub fn settle_auction() -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::FinalizePresale {
+            presale_info: pda::presale_info().0,
+        },
+        args::SettleAuction {},
+    )
+}
+
+/// Permissionless
+pub fn finalize_presale() -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::FinalizePresale {
+            presale_info: pda::presale_info().0,
+        },
+        args::FinalizePresale {},
+    )
+}
+
+pub fn claim_auction_refund(buyer: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::ClaimAuctionRefund {
+            buyer,
+            presale_info: pda::presale_info().0,
+            purchase_record: pda::purchase_record(&buyer).0,
+            sol_vault: pda::sol_vault().0,
+        },
+        args::ClaimAuctionRefund {},
+    )
+}
+
+pub fn claim_purchased_tokens(buyer: Pubkey, buyer_token_account: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        claim_accounts(buyer, buyer_token_account),
+        args::ClaimPurchasedTokens {},
+    )
+}
+
+pub fn release_vested(buyer: Pubkey, buyer_token_account: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        claim_accounts(buyer, buyer_token_account),
+        args::ReleaseVested {},
+    )
+}
+
+/// Refund after a failed sale. `payment_refunds` lists `(payment_mint, buyer_payment_account)`
+/// for every mint the buyer paid with, in `PresaleInfo::payment_mints` order.
+pub fn claim_refund(
+    buyer: Pubkey,
+    buyer_token_account: Pubkey,
+    payment_refunds: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut ix = instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::ClaimRefund {
+            buyer,
+            presale_info: pda::presale_info().0,
+            purchase_record: pda::purchase_record(&buyer).0,
+            token_vault: pda::presale_token_vault().0,
+            sol_vault: pda::sol_vault().0,
+            buyer_token_account,
+            token_program: anchor_spl::token::ID,
+        },
+        args::ClaimRefund {},
+    );
+    for (payment_mint, buyer_payment_account) in payment_refunds {
+        ix.accounts.push(AccountMeta::new(pda::payment_vault(payment_mint).0, false));
+        ix.accounts.push(AccountMeta::new(*buyer_payment_account, false));
+    }
+    ix
+}
+
+/// Permissionless. `payees` are the payee wallets in `PresaleInfo::payees` order.
+pub fn distribute_proceeds(payees: &[Pubkey]) -> Instruction {
+    let mut ix = instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::DistributeProceeds {
+            presale_info: pda::presale_info().0,
+            sol_vault: pda::sol_vault().0,
+        },
+        args::DistributeProceeds {},
+    );
+    ix.accounts
+        .extend(payees.iter().map(|payee| AccountMeta::new(*payee, false)));
+    ix
+}
+
+pub fn withdraw_sol(authority: Pubkey, amount: u64) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::WithdrawSOL {
+            authority,
+            presale_info: pda::presale_info().0,
+            sol_vault: pda::sol_vault().0,
+        },
+        args::WithdrawSol { amount },
+    )
+}
+
+pub fn withdraw_payment_tokens(
+    authority: Pubkey,
+    payment_mint: Pubkey,
+    authority_payment_account: Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::WithdrawPaymentTokens {
+            authority,
+            presale_info: pda::presale_info().0,
+            payment_mint,
+            payment_vault: pda::payment_vault(&payment_mint).0,
+            authority_payment_account,
+            token_program: anchor_spl::token::ID,
+        },
+        args::WithdrawPaymentTokens { amount },
+    )
+}
+
+pub fn withdraw_remaining_tokens(authority: Pubkey, authority_token_account: Pubkey) -> Instruction {
+    instruction(
+        PRESALE_PROGRAM_ID,
+        accounts::WithdrawRemainingTokens {
+            authority,
+            presale_info: pda::presale_info().0,
+            authority_token_account,
+            token_vault: pda::presale_token_vault().0,
+            token_program: anchor_spl::token::ID,
+        },
+        args::WithdrawRemainingTokens {},
+    )
+}
+
+fn update_accounts(authority: Pubkey) -> accounts::UpdatePresale {
+    accounts::UpdatePresale {
+        authority,
+        presale_info: pda::presale_info().0,
+    }
+}
+
+fn claim_accounts(buyer: Pubkey, buyer_token_account: Pubkey) -> accounts::ClaimPurchasedTokens {
+    accounts::ClaimPurchasedTokens {
+        buyer,
+        presale_info: pda::presale_info().0,
+        purchase_record: pda::purchase_record(&buyer).0,
+        token_vault: pda::presale_token_vault().0,
+        buyer_token_account,
+        token_program: anchor_spl::token::ID,
+    }
+}
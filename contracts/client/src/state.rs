@@ -0,0 +1,45 @@
+//! Account and return data deserializers.
+//!
+//! Account helpers take the raw account data as fetched over RPC and check
+//! the Anchor discriminator before decoding.
+
+use anchor_lang::prelude::Result;
+use anchor_lang::{AccountDeserialize, AnchorDeserialize};
+use cat_coin_contracts::airdrop::{AirdropInfo, ClaimRecord};
+use cat_coin_contracts::cat_token::TokenInfo;
+use cat_coin_contracts::presale::{PresaleInfo, PurchaseRecord, ReferralRecord, SolVault};
+
+pub fn token_info(data: &[u8]) -> Result<TokenInfo> {
+    TokenInfo::try_deserialize(&mut &data[..])
+}
+
+pub fn airdrop_info(data: &[u8]) -> Result<AirdropInfo> {
+    AirdropInfo::try_deserialize(&mut &data[..])
+}
+
+pub fn claim_record(data: &[u8]) -> Result<ClaimRecord> {
+    ClaimRecord::try_deserialize(&mut &data[..])
+}
+
+pub fn presale_info(data: &[u8]) -> Result<PresaleInfo> {
+    PresaleInfo::try_deserialize(&mut &data[..])
+}
+
+pub fn purchase_record(data: &[u8]) -> Result<PurchaseRecord> {
+    PurchaseRecord::try_deserialize(&mut &data[..])
+}
+
+pub fn referral_record(data: &[u8]) -> Result<ReferralRecord> {
+    ReferralRecord::try_deserialize(&mut &data[..])
+}
+
+pub fn sol_vault(data: &[u8]) -> Result<SolVault> {
+    SolVault::try_deserialize(&mut &data[..])
+}
+
+/// Decode an instruction's return data, e.g. the `u64` from `buy_tokens`, the
+/// `PurchaseQuote` from `quote_purchase` or the `SolVaultBalance` from
+/// `get_sol_vault_balance`
+pub fn return_data<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    Ok(T::try_from_slice(data)?)
+}
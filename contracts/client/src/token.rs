@@ -0,0 +1,104 @@
+//! Instruction builders for `cat_token`
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{system_program, sysvar};
+use cat_coin_contracts::cat_token::{accounts, instruction as args};
+
+use crate::{instruction, pda, TOKEN_PROGRAM_ID};
+
+/// Create `TokenInfo` and the CAT mint; `mint` is a fresh keypair that must sign
+pub fn initialize_token(
+    authority: Pubkey,
+    mint: Pubkey,
+    decimals: u8,
+    name: String,
+    symbol: String,
+) -> Instruction {
+    instruction(
+        TOKEN_PROGRAM_ID,
+        accounts::InitializeToken {
+            authority,
+            token_info: pda::token_info().0,
+            mint,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        args::InitializeToken { decimals, name, symbol },
+    )
+}
+
+pub fn mint_tokens(authority: Pubkey, mint: Pubkey, to: Pubkey, amount: u64) -> Instruction {
+    instruction(
+        TOKEN_PROGRAM_ID,
+        accounts::MintTokens {
+            authority,
+            token_info: pda::token_info().0,
+            mint,
+            to,
+            token_program: anchor_spl::token::ID,
+        },
+        args::MintTokens { amount },
+    )
+}
+
+pub fn transfer_tokens(authority: Pubkey, from: Pubkey, to: Pubkey, amount: u64) -> Instruction {
+    instruction(
+        TOKEN_PROGRAM_ID,
+        accounts::TransferTokens {
+            authority,
+            from,
+            to,
+            token_program: anchor_spl::token::ID,
+        },
+        args::TransferTokens { amount },
+    )
+}
+
+pub fn burn_tokens(authority: Pubkey, mint: Pubkey, from: Pubkey, amount: u64) -> Instruction {
+    instruction(
+        TOKEN_PROGRAM_ID,
+        accounts::BurnTokens {
+            authority,
+            token_info: pda::token_info().0,
+            mint,
+            from,
+            token_program: anchor_spl::token::ID,
+        },
+        args::BurnTokens { amount },
+    )
+}
+
+pub fn propose_authority(authority: Pubkey, new_authority: Pubkey) -> Instruction {
+    instruction(
+        TOKEN_PROGRAM_ID,
+        accounts::UpdateTokenAuthority {
+            authority,
+            token_info: pda::token_info().0,
+        },
+        args::ProposeAuthority { new_authority },
+    )
+}
+
+pub fn accept_authority(new_authority: Pubkey) -> Instruction {
+    instruction(
+        TOKEN_PROGRAM_ID,
+        accounts::AcceptTokenAuthority {
+            new_authority,
+            token_info: pda::token_info().0,
+        },
+        args::AcceptAuthority {},
+    )
+}
+
+pub fn cancel_authority_transfer(authority: Pubkey) -> Instruction {
+    instruction(
+        TOKEN_PROGRAM_ID,
+        accounts::UpdateTokenAuthority {
+            authority,
+            token_info: pda::token_info().0,
+        },
+        args::CancelAuthorityTransfer {},
+    )
+}
@@ -1,11 +1,8 @@
-use anchor_lang::prelude::*;
+//! All CAT COIN programs under one crate for off-chain users (client SDK, CLI).
+//! Each program is built and deployed from its own crate in `programs/`.
 
-pub mod cat_token;
-pub mod airdrop;
-pub mod presale;
-
-pub use cat_token::*;
-pub use airdrop::*;
-pub use presale::*;
-
-declare_id!("CATCoinMainProgram1111111111111111111111111");
\ No newline at end of file
+pub use cat_airdrop as airdrop;
+pub use cat_merkle as merkle;
+pub use cat_multisig as multisig;
+pub use cat_presale as presale;
+pub use cat_token;
@@ -0,0 +1,343 @@
+//! Off-chain companion to `cat_airdrop` Merkle claims.
+//!
+//! `build` turns a `wallet,amount` CSV into the root passed to
+//! `initialize_airdrop` plus one JSON proof file per wallet, and `verify`
+//! re-checks every proof file against the root with the same hashing the
+//! program uses.
+
+mod tree;
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use cat_coin_contracts::merkle;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+
+use crate::tree::MerkleTree;
+
+const ROOT_FILE: &str = "root.json";
+const PROOFS_DIR: &str = "proofs";
+
+#[derive(Parser)]
+#[command(name = "cat-airdrop-merkle", about = "Build and verify CAT COIN airdrop Merkle proofs")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build the Merkle root and per-wallet proof files from a CSV allowlist
+    Build {
+        /// CSV file with a `wallet,amount` header
+        #[arg(long)]
+        input: PathBuf,
+        /// Planned `total_airdrop_amount`; the allowlist may not exceed it
+        #[arg(long)]
+        total_airdrop_amount: u64,
+        /// Directory that receives `root.json` and `proofs/<wallet>.json`
+        #[arg(long)]
+        out_dir: PathBuf,
+        /// Replace the `root.json` and `proofs/` of an earlier build in `out_dir`
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Verify every proof file in a build directory against its root
+    Verify {
+        /// Directory previously written by `build`
+        #[arg(long)]
+        dir: PathBuf,
+        /// Optional source CSV; every row must have a matching proof file
+        #[arg(long)]
+        input: Option<PathBuf>,
+    },
+}
+
+#[derive(Deserialize)]
+struct CsvRow {
+    wallet: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RootFile {
+    merkle_root: String,
+    total_airdrop_amount: u64,
+    total_allocated: u64,
+    wallets: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProofFile {
+    wallet: String,
+    index: u64,
+    amount: u64,
+    proof: Vec<String>,
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Build {
+            input,
+            total_airdrop_amount,
+            out_dir,
+            overwrite,
+        } => build(&input, total_airdrop_amount, &out_dir, overwrite),
+        Command::Verify { dir, input } => verify(&dir, input.as_deref()),
+    }
+}
+
+/// Read the allowlist, rejecting bad pubkeys, zero amounts and conflicting duplicates
+fn read_allowlist(path: &Path) -> Result<Vec<(Pubkey, u64)>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+
+    let mut entries = Vec::new();
+    let mut seen: HashMap<Pubkey, u64> = HashMap::new();
+    for (row_index, row) in reader.deserialize::<CsvRow>().enumerate() {
+        // Header is line 1
+        let line = row_index + 2;
+        let row = row.with_context(|| format!("line {}: malformed row", line))?;
+        let wallet = Pubkey::from_str(&row.wallet)
+            .map_err(|_| anyhow!("line {}: invalid base58 pubkey {:?}", line, row.wallet))?;
+        if row.amount == 0 {
+            bail!("line {}: amount for {} is zero", line, wallet);
+        }
+
+        match seen.get(&wallet) {
+            Some(&amount) if amount == row.amount => {
+                eprintln!("line {}: skipping duplicate entry for {}", line, wallet);
+            }
+            Some(&amount) => bail!(
+                "line {}: {} is listed twice with different amounts ({} and {})",
+                line,
+                wallet,
+                amount,
+                row.amount
+            ),
+            None => {
+                seen.insert(wallet, row.amount);
+                entries.push((wallet, row.amount));
+            }
+        }
+    }
+
+    if entries.is_empty() {
+        bail!("{} contains no allowlist entries", path.display());
+    }
+    Ok(entries)
+}
+
+fn build(input: &Path, total_airdrop_amount: u64, out_dir: &Path, overwrite: bool) -> Result<()> {
+    let entries = read_allowlist(input)?;
+
+    let total_allocated = entries
+        .iter()
+        .try_fold(0u64, |sum, (_, amount)| sum.checked_add(*amount))
+        .ok_or_else(|| anyhow!("allocated amounts overflow u64"))?;
+    if total_allocated > total_airdrop_amount {
+        bail!(
+            "allowlist allocates {} but total_airdrop_amount is {}",
+            total_allocated,
+            total_airdrop_amount
+        );
+    }
+
+    let tree = MerkleTree::new(&entries);
+    prepare_out_dir(out_dir, overwrite)?;
+    let proofs_dir = out_dir.join(PROOFS_DIR);
+    fs::create_dir_all(&proofs_dir)
+        .with_context(|| format!("failed to create {}", proofs_dir.display()))?;
+
+    for (index, (wallet, amount)) in entries.iter().enumerate() {
+        let proof_file = ProofFile {
+            wallet: wallet.to_string(),
+            index: index as u64,
+            amount: *amount,
+            proof: tree.proof(index).iter().map(hex::encode).collect(),
+        };
+        write_json(&proofs_dir.join(format!("{}.json", wallet)), &proof_file)?;
+    }
+
+    let root_file = RootFile {
+        merkle_root: hex::encode(tree.root()),
+        total_airdrop_amount,
+        total_allocated,
+        wallets: entries.len() as u64,
+    };
+    write_json(&out_dir.join(ROOT_FILE), &root_file)?;
+
+    println!("Merkle root: {}", root_file.merkle_root);
+    println!("Wallets: {}", root_file.wallets);
+    println!("Total allocated: {} of {}", total_allocated, total_airdrop_amount);
+
+    Ok(())
+}
+
+/// Make sure no proof from an earlier build survives next to the new root
+fn prepare_out_dir(out_dir: &Path, overwrite: bool) -> Result<()> {
+    let root_path = out_dir.join(ROOT_FILE);
+    let proofs_dir = out_dir.join(PROOFS_DIR);
+    if !root_path.exists() && !proofs_dir.exists() {
+        return Ok(());
+    }
+    if !overwrite {
+        bail!(
+            "{} already holds a build, pass --overwrite to replace it",
+            out_dir.display()
+        );
+    }
+
+    if root_path.exists() {
+        fs::remove_file(&root_path)
+            .with_context(|| format!("failed to remove {}", root_path.display()))?;
+    }
+    if proofs_dir.exists() {
+        fs::remove_dir_all(&proofs_dir)
+            .with_context(|| format!("failed to remove {}", proofs_dir.display()))?;
+    }
+    Ok(())
+}
+
+fn verify(dir: &Path, input: Option<&Path>) -> Result<()> {
+    let root_file: RootFile = read_json(&dir.join(ROOT_FILE))?;
+    let root = decode_hash(&root_file.merkle_root)?;
+
+    let mut indices = BTreeSet::new();
+    let mut allocations: HashMap<Pubkey, u64> = HashMap::new();
+    let mut total_allocated = 0u64;
+
+    let proofs_dir = dir.join(PROOFS_DIR);
+    let listing = fs::read_dir(&proofs_dir)
+        .with_context(|| format!("failed to read {}", proofs_dir.display()))?;
+    for dir_entry in listing {
+        let path = dir_entry?.path();
+        let proof_file: ProofFile = read_json(&path)?;
+        let wallet = Pubkey::from_str(&proof_file.wallet)
+            .map_err(|_| anyhow!("{}: invalid wallet {:?}", path.display(), proof_file.wallet))?;
+        let proof = proof_file
+            .proof
+            .iter()
+            .map(|node| decode_hash(node))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("{}: malformed proof", path.display()))?;
+
+        let leaf = merkle::leaf_hash(&wallet, proof_file.amount);
+        if !merkle::verify(&proof, &root, leaf, proof_file.index) {
+            bail!("{}: proof does not match root {}", path.display(), root_file.merkle_root);
+        }
+        if !indices.insert(proof_file.index) {
+            bail!("{}: index {} is used twice", path.display(), proof_file.index);
+        }
+
+        total_allocated = total_allocated
+            .checked_add(proof_file.amount)
+            .ok_or_else(|| anyhow!("allocated amounts overflow u64"))?;
+        allocations.insert(wallet, proof_file.amount);
+    }
+
+    if allocations.len() as u64 != root_file.wallets {
+        bail!(
+            "found {} proof files but {} records {} wallets",
+            allocations.len(),
+            ROOT_FILE,
+            root_file.wallets
+        );
+    }
+    if total_allocated != root_file.total_allocated {
+        bail!(
+            "proofs allocate {} but {} records {}",
+            total_allocated,
+            ROOT_FILE,
+            root_file.total_allocated
+        );
+    }
+
+    if let Some(input) = input {
+        for (wallet, amount) in read_allowlist(input)? {
+            match allocations.get(&wallet) {
+                Some(&proven) if proven == amount => {}
+                Some(&proven) => bail!(
+                    "{} is allocated {} in the CSV but {} in its proof",
+                    wallet,
+                    amount,
+                    proven
+                ),
+                None => bail!("{} has no proof file", wallet),
+            }
+        }
+    }
+
+    println!(
+        "Verified {} proofs against root {}",
+        root_file.wallets, root_file.merkle_root
+    );
+
+    Ok(())
+}
+
+fn decode_hash(value: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(value).with_context(|| format!("invalid hex {:?}", value))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("expected a 32 byte hash, got {:?}", value))
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let contents = serde_json::to_string_pretty(value)?;
+    fs::write(path, contents).with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cat-airdrop-merkle-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_allowlist(dir: &Path, name: &str, entries: &[(Pubkey, u64)]) -> PathBuf {
+        let path = dir.join(name);
+        let mut csv = String::from("wallet,amount\n");
+        for (wallet, amount) in entries {
+            csv.push_str(&format!("{},{}\n", wallet, amount));
+        }
+        fs::write(&path, csv).unwrap();
+        path
+    }
+
+    #[test]
+    fn rebuild_requires_overwrite_and_drops_stale_proofs() {
+        let dir = temp_dir("rebuild");
+        let out_dir = dir.join("out");
+        let wallets: Vec<(Pubkey, u64)> = (1..=5).map(|amount| (Pubkey::new_unique(), amount)).collect();
+        let full = write_allowlist(&dir, "full.csv", &wallets);
+        let smaller = write_allowlist(&dir, "smaller.csv", &wallets[..3]);
+
+        build(&full, 100, &out_dir, false).unwrap();
+        verify(&out_dir, Some(&full)).unwrap();
+
+        assert!(build(&smaller, 100, &out_dir, false).is_err());
+        build(&smaller, 100, &out_dir, true).unwrap();
+        verify(&out_dir, Some(&smaller)).unwrap();
+        assert_eq!(fs::read_dir(out_dir.join(PROOFS_DIR)).unwrap().count(), 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
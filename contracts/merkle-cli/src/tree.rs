@@ -0,0 +1,100 @@
+use anchor_lang::prelude::Pubkey;
+use cat_coin_contracts::merkle;
+
+/// Binary Merkle tree laid out the way `cat_airdrop` verifies proofs.
+///
+/// A level with an odd number of nodes pairs its last node with itself, so the
+/// proof for that node carries its own hash as the sibling.
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Build a tree over `(wallet, amount)` entries; leaf `i` is entry `i`
+    pub fn new(entries: &[(Pubkey, u64)]) -> Self {
+        assert!(!entries.is_empty(), "cannot build a Merkle tree without leaves");
+
+        let leaves: Vec<[u8; 32]> = entries
+            .iter()
+            .map(|(wallet, amount)| merkle::leaf_hash(wallet, *amount))
+            .collect();
+
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| merkle::node_hash(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                .collect();
+            levels.push(next);
+        }
+
+        Self { levels }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// Sibling hashes from the leaf at `index` up to (but excluding) the root
+    pub fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::with_capacity(self.levels.len() - 1);
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = position ^ 1;
+            proof.push(*level.get(sibling).unwrap_or(&level[position]));
+            position /= 2;
+        }
+        proof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(count: usize) -> Vec<(Pubkey, u64)> {
+        (0..count).map(|i| (Pubkey::new_unique(), 1_000 + i as u64)).collect()
+    }
+
+    #[test]
+    fn leaves_are_program_leaf_hashes() {
+        let entries = entries(3);
+        let tree = MerkleTree::new(&entries);
+        for (leaf, (wallet, amount)) in tree.levels[0].iter().zip(&entries) {
+            assert_eq!(*leaf, merkle::leaf_hash(wallet, *amount));
+        }
+    }
+
+    #[test]
+    fn odd_level_pairs_last_node_with_itself() {
+        let entries = entries(3);
+        let tree = MerkleTree::new(&entries);
+        let leaves: Vec<[u8; 32]> = entries
+            .iter()
+            .map(|(wallet, amount)| merkle::leaf_hash(wallet, *amount))
+            .collect();
+
+        let left = merkle::node_hash(&leaves[0], &leaves[1]);
+        let right = merkle::node_hash(&leaves[2], &leaves[2]);
+        assert_eq!(tree.root(), merkle::node_hash(&left, &right));
+        assert_eq!(tree.proof(2), vec![leaves[2], left]);
+    }
+
+    #[test]
+    fn every_proof_verifies_with_the_program() {
+        // Covers single-leaf trees, full levels and odd-sized levels at every height
+        for count in 1..=17 {
+            let entries = entries(count);
+            let tree = MerkleTree::new(&entries);
+            let root = tree.root();
+            for (index, (wallet, amount)) in entries.iter().enumerate() {
+                let leaf = merkle::leaf_hash(wallet, *amount);
+                let proof = tree.proof(index);
+                assert!(merkle::verify(&proof, &root, leaf, index as u64), "{} leaves, index {}", count, index);
+                assert!(!merkle::verify(&proof, &root, merkle::leaf_hash(wallet, amount + 1), index as u64));
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+/// Domain separator for leaf hashes so a leaf can never be passed off as an inner node
+pub const LEAF_PREFIX: u8 = 0x00;
+/// Domain separator for inner node hashes
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// Hash an allowlist entry: keccak256(0x00 || wallet || amount as little-endian u64)
+pub fn leaf_hash(wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[&[LEAF_PREFIX], wallet.as_ref(), &amount.to_le_bytes()]).0
+}
+
+/// Hash two sibling nodes: keccak256(0x01 || left || right)
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[&[NODE_PREFIX], left, right]).0
+}
+
+/// Verify that `leaf` sits at position `index` of the tree committed to by `root`.
+///
+/// Bit `i` of `index` tells whether the node at level `i` is a right child, in
+/// which case `proof[i]` is hashed on the left.
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32], index: u64) -> bool {
+    if proof.len() >= 64 || index >> proof.len() != 0 {
+        return false;
+    }
+
+    let mut computed = leaf;
+    for (level, sibling) in proof.iter().enumerate() {
+        computed = if (index >> level) & 1 == 1 {
+            node_hash(sibling, &computed)
+        } else {
+            node_hash(&computed, sibling)
+        };
+    }
+
+    computed == *root
+}
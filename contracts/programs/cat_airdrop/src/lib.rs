@@ -0,0 +1,1276 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{ed25519_program, sysvar::instructions as sysvar_instructions};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use cat_merkle as merkle;
+
+declare_id!("CATCoinAirdropProgram1111111111111111111111");
+
+/// Delay before a queued settings change can be executed, unless changed through the timelock itself
+pub const DEFAULT_TIMELOCK_DELAY: i64 = 24 * 60 * 60;
+/// Longest timelock delay a queued update can set
+pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+
+#[program]
+pub mod cat_airdrop {
+    use super::*;
+
+    /// Initialize an airdrop campaign
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_airdrop(
+        ctx: Context<InitializeAirdrop>,
+        campaign_id: u64,
+        total_airdrop_amount: u64,
+        merkle_root: [u8; 32],
+        start_time: i64,
+        end_time: i64,
+        vesting_cliff: i64,
+        vesting_duration: i64,
+        claim_fee_lamports: u64,
+        attestor: Pubkey,
+    ) -> Result<()> {
+        // A zero duration means claims are paid out immediately
+        require!(
+            vesting_cliff >= 0 && vesting_duration >= vesting_cliff,
+            ErrorCode::InvalidVestingSchedule
+        );
+        require_valid_claim_fee(claim_fee_lamports)?;
+
+        let airdrop_info = &mut ctx.accounts.airdrop_info;
+        airdrop_info.campaign_id = campaign_id;
+        airdrop_info.creator = ctx.accounts.authority.key();
+        airdrop_info.bump = ctx.bumps.airdrop_info;
+        airdrop_info.authority = ctx.accounts.authority.key();
+        airdrop_info.token_mint = ctx.accounts.token_mint.key();
+        airdrop_info.token_vault = ctx.accounts.token_vault.key();
+        airdrop_info.total_airdrop_amount = total_airdrop_amount;
+        airdrop_info.merkle_root = merkle_root; // Root of the (wallet, amount) allowlist
+        airdrop_info.claimed_amount = 0;
+        airdrop_info.released_amount = 0;
+        airdrop_info.total_claimers = 0;
+        airdrop_info.start_time = start_time;
+        airdrop_info.end_time = end_time;
+        airdrop_info.is_active = true;
+        airdrop_info.closed = false;
+        airdrop_info.vesting_cliff = vesting_cliff;
+        airdrop_info.vesting_duration = vesting_duration;
+        airdrop_info.claim_fee_lamports = claim_fee_lamports; // 0.01 SOL = 10,000,000 lamports
+        airdrop_info.fees_collected = 0;
+        airdrop_info.attestor = attestor; // Pubkey::default() disables attested claims
+        airdrop_info.pending_authority = Pubkey::default();
+        airdrop_info.timelock_delay = DEFAULT_TIMELOCK_DELAY;
+        airdrop_info.pending_update = None;
+        airdrop_info.pending_update_eta = 0;
+
+        emit!(AirdropInitialized {
+            campaign: airdrop_info.key(),
+            campaign_id,
+            authority: airdrop_info.authority,
+            token_mint: airdrop_info.token_mint,
+            total_airdrop_amount,
+            merkle_root,
+            start_time,
+            end_time,
+            vesting_cliff,
+            vesting_duration,
+            claim_fee_lamports,
+            attestor,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Claim airdrop tokens allocated to the signer in the Merkle allowlist
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        // Verify the (user, amount) leaf against the allowlist root
+        let leaf = merkle::leaf_hash(&ctx.accounts.user.key(), amount);
+        require!(
+            merkle::verify(&proof, &ctx.accounts.airdrop_info.merkle_root, leaf, index),
+            ErrorCode::InvalidProof
+        );
+
+        ctx.accounts.process_claim(amount)
+    }
+
+    /// Claim airdrop tokens attested by the campaign's backend signer.
+    ///
+    /// The transaction must carry an ed25519 program instruction, right before
+    /// this one, in which `attestor` signs `(campaign, claimant, amount, expiry)`.
+    pub fn claim_attested(
+        ctx: Context<ClaimAttested>,
+        amount: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        let claim = &ctx.accounts.claim;
+        let attestor = claim.airdrop_info.attestor;
+        require!(attestor != Pubkey::default(), ErrorCode::AttestationDisabled);
+        require!(
+            Clock::get()?.unix_timestamp <= expiry,
+            ErrorCode::AttestationExpired
+        );
+
+        let message = attestation_message(
+            &claim.airdrop_info.key(),
+            &claim.user.key(),
+            amount,
+            expiry,
+        );
+        verify_attestation(&ctx.accounts.instructions.to_account_info(), &attestor, &message)?;
+
+        ctx.accounts.claim.process_claim(amount)
+    }
+
+    /// Release the vested portion of a claim that has not been withdrawn yet
+    pub fn withdraw_vested(
+        ctx: Context<WithdrawVested>,
+    ) -> Result<()> {
+        let airdrop_info = &mut ctx.accounts.airdrop_info;
+        let claim_record = &mut ctx.accounts.claim_record;
+        let clock = Clock::get()?;
+
+        require!(claim_record.has_claimed, ErrorCode::NothingToWithdraw);
+
+        let releasable = claim_record.vested_amount(clock.unix_timestamp) - claim_record.amount_released;
+        require!(releasable > 0, ErrorCode::NothingToWithdraw);
+
+        claim_record.amount_released += releasable;
+        airdrop_info.released_amount += releasable;
+
+        let creator = airdrop_info.creator;
+        let campaign_id = airdrop_info.campaign_id.to_le_bytes();
+        let seeds = &[
+            b"airdrop_info".as_ref(),
+            creator.as_ref(),
+            campaign_id.as_ref(),
+            &[airdrop_info.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.token_vault.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: airdrop_info.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, releasable)?;
+
+        emit!(VestedAirdropWithdrawn {
+            campaign: airdrop_info.key(),
+            user: ctx.accounts.user.key(),
+            amount: releasable,
+            total_released: claim_record.amount_released,
+            amount_claimed: claim_record.amount_claimed,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Add tokens to airdrop vault (only authority)
+    pub fn fund_airdrop(
+        ctx: Context<FundAirdrop>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority_token_account.to_account_info(),
+            to: ctx.accounts.token_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(AirdropFunded {
+            campaign: ctx.accounts.airdrop_info.key(),
+            funder: ctx.accounts.authority.key(),
+            amount,
+            vault_balance: ctx.accounts.token_vault.amount + amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Queue a settings change that can be executed after `timelock_delay` (only authority).
+    /// The queued change is visible on `AirdropInfo` until it is executed or cancelled.
+    pub fn queue_airdrop_update(
+        ctx: Context<UpdateAirdrop>,
+        update: AirdropUpdate,
+    ) -> Result<()> {
+        let airdrop_info = &mut ctx.accounts.airdrop_info;
+        let clock = Clock::get()?;
+
+        require!(airdrop_info.pending_update.is_none(), ErrorCode::UpdateAlreadyQueued);
+        if let Some(claim_fee_lamports) = update.new_claim_fee_lamports {
+            require_valid_claim_fee(claim_fee_lamports)?;
+        }
+        if let Some(timelock_delay) = update.new_timelock_delay {
+            require!(
+                (0..=MAX_TIMELOCK_DELAY).contains(&timelock_delay),
+                ErrorCode::InvalidTimelockDelay
+            );
+        }
+
+        let eta = clock
+            .unix_timestamp
+            .checked_add(airdrop_info.timelock_delay)
+            .ok_or(ErrorCode::MathOverflow)?;
+        airdrop_info.pending_update = Some(update.clone());
+        airdrop_info.pending_update_eta = eta;
+
+        emit!(AirdropUpdateQueued {
+            campaign: airdrop_info.key(),
+            update,
+            eta,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Apply the queued settings change once its delay has passed (only authority)
+    pub fn execute_airdrop_update(
+        ctx: Context<UpdateAirdrop>,
+    ) -> Result<()> {
+        let airdrop_info = &mut ctx.accounts.airdrop_info;
+        let clock = Clock::get()?;
+
+        let update = airdrop_info
+            .pending_update
+            .take()
+            .ok_or(ErrorCode::NoUpdateQueued)?;
+        require!(
+            clock.unix_timestamp >= airdrop_info.pending_update_eta,
+            ErrorCode::TimelockNotExpired
+        );
+        airdrop_info.pending_update_eta = 0;
+
+        if let Some(merkle_root) = update.new_merkle_root {
+            airdrop_info.merkle_root = merkle_root;
+        }
+
+        if let Some(end_time) = update.new_end_time {
+            airdrop_info.end_time = end_time;
+        }
+
+        if let Some(is_active) = update.new_is_active {
+            airdrop_info.is_active = is_active;
+            airdrop_info.closed = !is_active;
+        }
+
+        if let Some(claim_fee_lamports) = update.new_claim_fee_lamports {
+            airdrop_info.claim_fee_lamports = claim_fee_lamports;
+        }
+
+        if let Some(attestor) = update.new_attestor {
+            airdrop_info.attestor = attestor;
+        }
+
+        if let Some(timelock_delay) = update.new_timelock_delay {
+            airdrop_info.timelock_delay = timelock_delay;
+        }
+
+        emit!(AirdropUpdated {
+            campaign: airdrop_info.key(),
+            update,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Drop the queued settings change (only authority)
+    pub fn cancel_airdrop_update(
+        ctx: Context<UpdateAirdrop>,
+    ) -> Result<()> {
+        let airdrop_info = &mut ctx.accounts.airdrop_info;
+
+        require!(airdrop_info.pending_update.is_some(), ErrorCode::NoUpdateQueued);
+        airdrop_info.pending_update = None;
+        airdrop_info.pending_update_eta = 0;
+
+        emit!(AirdropUpdateCancelled {
+            campaign: airdrop_info.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Stop claims immediately, bypassing the timelock (only authority).
+    /// Resuming goes through `queue_airdrop_update`.
+    pub fn pause_airdrop(
+        ctx: Context<UpdateAirdrop>,
+    ) -> Result<()> {
+        let airdrop_info = &mut ctx.accounts.airdrop_info;
+        airdrop_info.is_active = false;
+
+        emit!(AirdropPaused {
+            campaign: airdrop_info.key(),
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Propose a new authority for the campaign; it takes over once it calls `accept_authority` (only authority)
+    pub fn propose_authority(
+        ctx: Context<UpdateAirdrop>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let airdrop_info = &mut ctx.accounts.airdrop_info;
+
+        require!(
+            new_authority != Pubkey::default() && new_authority != airdrop_info.authority,
+            ErrorCode::InvalidNewAuthority
+        );
+        airdrop_info.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            config: airdrop_info.key(),
+            authority: airdrop_info.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Complete a proposed authority transfer (only the pending authority)
+    pub fn accept_authority(
+        ctx: Context<AcceptAirdropAuthority>,
+    ) -> Result<()> {
+        let airdrop_info = &mut ctx.accounts.airdrop_info;
+
+        let previous_authority = airdrop_info.authority;
+        airdrop_info.authority = ctx.accounts.new_authority.key();
+        airdrop_info.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferred {
+            config: airdrop_info.key(),
+            previous_authority,
+            new_authority: airdrop_info.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw a proposed authority transfer (only authority)
+    pub fn cancel_authority_transfer(
+        ctx: Context<UpdateAirdrop>,
+    ) -> Result<()> {
+        let airdrop_info = &mut ctx.accounts.airdrop_info;
+
+        require!(
+            airdrop_info.pending_authority != Pubkey::default(),
+            ErrorCode::NoPendingAuthority
+        );
+        emit!(AuthorityTransferCancelled {
+            config: airdrop_info.key(),
+            authority: airdrop_info.authority,
+            cancelled_authority: airdrop_info.pending_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        airdrop_info.pending_authority = Pubkey::default();
+
+        Ok(())
+    }
+
+    /// Withdraw all collected claim fees (only authority)
+    pub fn withdraw_fees(
+        ctx: Context<WithdrawFees>,
+    ) -> Result<()> {
+        let airdrop_info_key = ctx.accounts.airdrop_info.key();
+        let amount = ctx.accounts.fee_vault.lamports();
+        require!(amount > 0, ErrorCode::NoFeesToWithdraw);
+
+        // The whole balance is moved so the vault never drops below rent exemption
+        let seeds = &[
+            b"fee_vault".as_ref(),
+            airdrop_info_key.as_ref(),
+            &[ctx.bumps.fee_vault],
+        ];
+        let signer = &[&seeds[..]];
+
+        let ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.fee_vault.key(),
+            &ctx.accounts.authority.key(),
+            amount,
+        );
+        anchor_lang::solana_program::program::invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.fee_vault.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+            signer,
+        )?;
+
+        emit!(ClaimFeesWithdrawn {
+            campaign: airdrop_info_key,
+            authority: ctx.accounts.authority.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw remaining tokens (only authority, after airdrop ends)
+    pub fn withdraw_remaining(
+        ctx: Context<WithdrawRemaining>,
+    ) -> Result<()> {
+        let airdrop_info = &ctx.accounts.airdrop_info;
+        let clock = Clock::get()?;
+
+        // Check if airdrop has ended, or was closed through the timelock. An emergency pause
+        // alone does not release the vault.
+        require!(
+            clock.unix_timestamp > airdrop_info.end_time || airdrop_info.closed,
+            ErrorCode::AirdropStillActive
+        );
+
+        // Tokens still vesting for claimers stay in the vault
+        let unreleased = airdrop_info.claimed_amount - airdrop_info.released_amount;
+        let remaining_balance = ctx.accounts.token_vault.amount.saturating_sub(unreleased);
+
+        if remaining_balance > 0 {
+            let creator = airdrop_info.creator;
+            let campaign_id = airdrop_info.campaign_id.to_le_bytes();
+            let seeds = &[
+                b"airdrop_info".as_ref(),
+                creator.as_ref(),
+                campaign_id.as_ref(),
+                &[airdrop_info.bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.token_vault.to_account_info(),
+                to: ctx.accounts.authority_token_account.to_account_info(),
+                authority: ctx.accounts.airdrop_info.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+            token::transfer(cpi_ctx, remaining_balance)?;
+
+            emit!(RemainingTokensWithdrawn {
+                campaign: ctx.accounts.airdrop_info.key(),
+                authority: ctx.accounts.authority.key(),
+                amount: remaining_balance,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// The fee vault is a plain system account, so every deposit must leave it rent exempt
+fn require_valid_claim_fee(claim_fee_lamports: u64) -> Result<()> {
+    require!(
+        claim_fee_lamports == 0 || claim_fee_lamports >= Rent::get()?.minimum_balance(0),
+        ErrorCode::ClaimFeeBelowRentExemption
+    );
+    Ok(())
+}
+
+/// Bytes the attestor signs for a claim: campaign || claimant || amount (LE) || expiry (LE)
+pub fn attestation_message(campaign: &Pubkey, claimant: &Pubkey, amount: u64, expiry: i64) -> Vec<u8> {
+    [
+        campaign.as_ref(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+        &expiry.to_le_bytes(),
+    ]
+    .concat()
+}
+
+/// Size of the `Ed25519SignatureOffsets` struct that follows the 2 byte header
+const ED25519_OFFSETS_SIZE: usize = 14;
+const ED25519_OFFSETS_START: usize = 2;
+
+/// Check that the instruction right before this one is an ed25519 program
+/// instruction in which `attestor` signed exactly `message`.
+///
+/// The ed25519 program has already verified the signature itself by the time
+/// we run; we only have to make sure it covered the key and bytes we expect.
+fn verify_attestation(instructions: &AccountInfo, attestor: &Pubkey, message: &[u8]) -> Result<()> {
+    let current_index = sysvar_instructions::load_current_index_checked(instructions)? as usize;
+    require!(current_index > 0, ErrorCode::MissingAttestation);
+
+    let ix = sysvar_instructions::load_instruction_at_checked(current_index - 1, instructions)?;
+    require!(
+        ix.program_id == ed25519_program::ID && ix.accounts.is_empty(),
+        ErrorCode::MissingAttestation
+    );
+
+    // Exactly one signature, with its offsets header in place
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE && data[0] == 1,
+        ErrorCode::InvalidAttestation
+    );
+    let read_u16 = |field: usize| {
+        let at = ED25519_OFFSETS_START + field * 2;
+        u16::from_le_bytes([data[at], data[at + 1]])
+    };
+
+    // Signature, key and message must all live inside the ed25519 instruction itself
+    require!(
+        read_u16(1) == u16::MAX && read_u16(3) == u16::MAX && read_u16(6) == u16::MAX,
+        ErrorCode::InvalidAttestation
+    );
+
+    let public_key_offset = read_u16(2) as usize;
+    let message_offset = read_u16(4) as usize;
+    let message_size = read_u16(5) as usize;
+    let signer = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidAttestation)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidAttestation)?;
+
+    require!(
+        signer == attestor.as_ref() && signed_message == message,
+        ErrorCode::InvalidAttestation
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64)]
+pub struct InitializeAirdrop<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AirdropInfo::INIT_SPACE,
+        seeds = [b"airdrop_info", authority.key().as_ref(), campaign_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub airdrop_info: Account<'info, AirdropInfo>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = token_mint,
+        token::authority = airdrop_info,
+        seeds = [b"token_vault", airdrop_info.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop_info", airdrop_info.creator.as_ref(), airdrop_info.campaign_id.to_le_bytes().as_ref()],
+        bump = airdrop_info.bump
+    )]
+    pub airdrop_info: Account<'info, AirdropInfo>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ClaimRecord::INIT_SPACE,
+        seeds = [b"claim_record", airdrop_info.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub claim_record: Account<'info, ClaimRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", airdrop_info.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault", airdrop_info.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = airdrop_info.token_mint,
+        token::authority = user,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> ClaimAirdrop<'info> {
+    /// Record and pay out a claim whose eligibility has already been proven
+    fn process_claim(&mut self, amount: u64) -> Result<()> {
+        let airdrop_info = &mut self.airdrop_info;
+        let claim_record = &mut self.claim_record;
+        let clock = Clock::get()?;
+
+        // Check if airdrop is active
+        require!(airdrop_info.is_active, ErrorCode::AirdropNotActive);
+
+        // Check time bounds
+        require!(
+            clock.unix_timestamp >= airdrop_info.start_time,
+            ErrorCode::AirdropNotStarted
+        );
+        require!(
+            clock.unix_timestamp <= airdrop_info.end_time,
+            ErrorCode::AirdropEnded
+        );
+
+        // Check if user has already claimed
+        require!(!claim_record.has_claimed, ErrorCode::AlreadyClaimed);
+
+        // Check if there are enough tokens left
+        require!(
+            airdrop_info.claimed_amount + amount <= airdrop_info.total_airdrop_amount,
+            ErrorCode::InsufficientTokens
+        );
+
+        // Collect the claim fee in the same transaction that delivers the claim
+        if airdrop_info.claim_fee_lamports > 0 {
+            let ix = anchor_lang::solana_program::system_instruction::transfer(
+                &self.user.key(),
+                &self.fee_vault.key(),
+                airdrop_info.claim_fee_lamports,
+            );
+            anchor_lang::solana_program::program::invoke(
+                &ix,
+                &[
+                    self.user.to_account_info(),
+                    self.fee_vault.to_account_info(),
+                ],
+            )?;
+            airdrop_info.fees_collected += airdrop_info.claim_fee_lamports;
+        }
+
+        // Mark as claimed
+        claim_record.campaign = airdrop_info.key();
+        claim_record.user = self.user.key();
+        claim_record.has_claimed = true;
+        claim_record.claimed_at = clock.unix_timestamp;
+        claim_record.amount_claimed = amount;
+        claim_record.amount_released = 0;
+        claim_record.vesting_cliff = airdrop_info.vesting_cliff;
+        claim_record.vesting_duration = airdrop_info.vesting_duration;
+
+        // Update airdrop stats
+        airdrop_info.claimed_amount += amount;
+        airdrop_info.total_claimers += 1;
+
+        // In vesting mode the claim only records the schedule
+        if airdrop_info.vesting_duration > 0 {
+            emit!(AirdropClaimed {
+                campaign: airdrop_info.key(),
+                campaign_id: airdrop_info.campaign_id,
+                user: self.user.key(),
+                amount,
+                fee_lamports: airdrop_info.claim_fee_lamports,
+                vested: true,
+                cliff_ends_at: claim_record.claimed_at + claim_record.vesting_cliff,
+                fully_vested_at: claim_record.claimed_at + claim_record.vesting_duration,
+                total_claimed: airdrop_info.claimed_amount,
+                total_claimers: airdrop_info.total_claimers,
+                timestamp: clock.unix_timestamp,
+            });
+            return Ok(());
+        }
+
+        claim_record.amount_released = amount;
+        airdrop_info.released_amount += amount;
+
+        // Transfer tokens from vault to user
+        let creator = airdrop_info.creator;
+        let campaign_id = airdrop_info.campaign_id.to_le_bytes();
+        let seeds = &[
+            b"airdrop_info".as_ref(),
+            creator.as_ref(),
+            campaign_id.as_ref(),
+            &[airdrop_info.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: self.token_vault.to_account_info(),
+            to: self.user_token_account.to_account_info(),
+            authority: airdrop_info.to_account_info(),
+        };
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(AirdropClaimed {
+            campaign: airdrop_info.key(),
+            campaign_id: airdrop_info.campaign_id,
+            user: self.user.key(),
+            amount,
+            fee_lamports: airdrop_info.claim_fee_lamports,
+            vested: false,
+            cliff_ends_at: clock.unix_timestamp,
+            fully_vested_at: clock.unix_timestamp,
+            total_claimed: airdrop_info.claimed_amount,
+            total_claimers: airdrop_info.total_claimers,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimAttested<'info> {
+    pub claim: ClaimAirdrop<'info>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVested<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop_info", airdrop_info.creator.as_ref(), airdrop_info.campaign_id.to_le_bytes().as_ref()],
+        bump = airdrop_info.bump
+    )]
+    pub airdrop_info: Account<'info, AirdropInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"claim_record", airdrop_info.key().as_ref(), user.key().as_ref()],
+        bump,
+        has_one = user
+    )]
+    pub claim_record: Account<'info, ClaimRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", airdrop_info.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = airdrop_info.token_mint,
+        token::authority = user,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FundAirdrop<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop_info", airdrop_info.creator.as_ref(), airdrop_info.campaign_id.to_le_bytes().as_ref()],
+        bump = airdrop_info.bump,
+        has_one = authority
+    )]
+    pub airdrop_info: Account<'info, AirdropInfo>,
+
+    #[account(mut)]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", airdrop_info.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAirdrop<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop_info", airdrop_info.creator.as_ref(), airdrop_info.campaign_id.to_le_bytes().as_ref()],
+        bump = airdrop_info.bump,
+        has_one = authority
+    )]
+    pub airdrop_info: Account<'info, AirdropInfo>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAirdropAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop_info", airdrop_info.creator.as_ref(), airdrop_info.campaign_id.to_le_bytes().as_ref()],
+        bump = airdrop_info.bump,
+        constraint = airdrop_info.pending_authority == new_authority.key() @ ErrorCode::NotPendingAuthority
+    )]
+    pub airdrop_info: Account<'info, AirdropInfo>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"airdrop_info", airdrop_info.creator.as_ref(), airdrop_info.campaign_id.to_le_bytes().as_ref()],
+        bump = airdrop_info.bump,
+        has_one = authority
+    )]
+    pub airdrop_info: Account<'info, AirdropInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault", airdrop_info.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawRemaining<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop_info", airdrop_info.creator.as_ref(), airdrop_info.campaign_id.to_le_bytes().as_ref()],
+        bump = airdrop_info.bump,
+        has_one = authority
+    )]
+    pub airdrop_info: Account<'info, AirdropInfo>,
+
+    #[account(mut)]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", airdrop_info.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AirdropInfo {
+    pub campaign_id: u64,
+    pub creator: Pubkey, // Initializing authority, part of the PDA seeds
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub total_airdrop_amount: u64,
+    pub merkle_root: [u8; 32],
+    pub claimed_amount: u64,
+    pub released_amount: u64,
+    pub total_claimers: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub is_active: bool,
+    pub closed: bool, // Deactivated through the timelock, unlike `pause_airdrop`
+    pub vesting_cliff: i64, // Seconds after claim before anything unlocks
+    pub vesting_duration: i64, // Seconds after claim until fully unlocked, 0 = no vesting
+    pub claim_fee_lamports: u64,
+    pub fees_collected: u64,
+    pub attestor: Pubkey,
+    pub pending_authority: Pubkey, // Default = no transfer pending
+    pub timelock_delay: i64, // Seconds between queueing and executing a settings change
+    pub pending_update: Option<AirdropUpdate>,
+    pub pending_update_eta: i64,
+}
+
+/// Settings change queued behind the timelock, `None` fields are left unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct AirdropUpdate {
+    pub new_merkle_root: Option<[u8; 32]>,
+    pub new_end_time: Option<i64>,
+    pub new_is_active: Option<bool>,
+    pub new_claim_fee_lamports: Option<u64>,
+    pub new_attestor: Option<Pubkey>,
+    pub new_timelock_delay: Option<i64>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimRecord {
+    pub campaign: Pubkey,
+    pub user: Pubkey,
+    pub has_claimed: bool,
+    pub claimed_at: i64,
+    pub amount_claimed: u64,
+    pub amount_released: u64,
+    pub vesting_cliff: i64,
+    pub vesting_duration: i64,
+}
+
+impl ClaimRecord {
+    /// Amount unlocked at `now`, vesting linearly from `claimed_at` once the cliff has passed
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        let elapsed = now - self.claimed_at;
+        if elapsed < self.vesting_cliff {
+            return 0;
+        }
+        if elapsed >= self.vesting_duration {
+            return self.amount_claimed;
+        }
+        (self.amount_claimed as u128 * elapsed as u128 / self.vesting_duration as u128) as u64
+    }
+}
+
+#[event]
+pub struct AirdropInitialized {
+    pub campaign: Pubkey,
+    pub campaign_id: u64,
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub total_airdrop_amount: u64,
+    pub merkle_root: [u8; 32],
+    pub start_time: i64,
+    pub end_time: i64,
+    pub vesting_cliff: i64,
+    pub vesting_duration: i64,
+    pub claim_fee_lamports: u64,
+    pub attestor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropClaimed {
+    pub campaign: Pubkey,
+    pub campaign_id: u64,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub fee_lamports: u64,
+    pub vested: bool, // Recorded into a vesting schedule instead of paid out
+    pub cliff_ends_at: i64,
+    pub fully_vested_at: i64,
+    pub total_claimed: u64,
+    pub total_claimers: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestedAirdropWithdrawn {
+    pub campaign: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub total_released: u64,
+    pub amount_claimed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropFunded {
+    pub campaign: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropUpdateQueued {
+    pub campaign: Pubkey,
+    pub update: AirdropUpdate,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropUpdated {
+    pub campaign: Pubkey,
+    pub update: AirdropUpdate,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropUpdateCancelled {
+    pub campaign: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropPaused {
+    pub campaign: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub config: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClaimFeesWithdrawn {
+    pub campaign: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RemainingTokensWithdrawn {
+    pub campaign: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Airdrop is not active")]
+    AirdropNotActive,
+    #[msg("Airdrop has not started yet")]
+    AirdropNotStarted,
+    #[msg("Airdrop has ended")]
+    AirdropEnded,
+    #[msg("User has already claimed")]
+    AlreadyClaimed,
+    #[msg("Insufficient tokens in vault")]
+    InsufficientTokens,
+    #[msg("Airdrop is still active")]
+    AirdropStillActive,
+    #[msg("Invalid Merkle proof")]
+    InvalidProof,
+    #[msg("Vesting cliff must not exceed vesting duration")]
+    InvalidVestingSchedule,
+    #[msg("No vested tokens to withdraw")]
+    NothingToWithdraw,
+    #[msg("Claim fee is below the fee vault rent-exempt minimum")]
+    ClaimFeeBelowRentExemption,
+    #[msg("No claim fees to withdraw")]
+    NoFeesToWithdraw,
+    #[msg("Attested claims are disabled for this campaign")]
+    AttestationDisabled,
+    #[msg("Attestation has expired")]
+    AttestationExpired,
+    #[msg("Missing ed25519 attestation instruction")]
+    MissingAttestation,
+    #[msg("Attestation does not match the claim")]
+    InvalidAttestation,
+    #[msg("New authority must differ from the current one")]
+    InvalidNewAuthority,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    #[msg("An update is already queued")]
+    UpdateAlreadyQueued,
+    #[msg("No update is queued")]
+    NoUpdateQueued,
+    #[msg("Timelock delay has not passed")]
+    TimelockNotExpired,
+    #[msg("Invalid timelock delay")]
+    InvalidTimelockDelay,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::instruction::Instruction;
+    use anchor_lang::solana_program::sysvar;
+    use solana_sdk::ed25519_instruction::new_ed25519_instruction;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+        let keypair = ed25519_dalek::Keypair::from_bytes(&signer.to_bytes()).unwrap();
+        new_ed25519_instruction(&keypair, message)
+    }
+
+    fn set_offset(ix: &mut Instruction, field: usize, value: u16) {
+        let at = ED25519_OFFSETS_START + field * 2;
+        ix.data[at..at + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// Run `verify_attestation` as the last of `preceding` instructions in a transaction
+    fn verify(preceding: &[Instruction], attestor: &Pubkey, message: &[u8]) -> Result<()> {
+        let claim = Instruction::new_with_bytes(crate::ID, &[], vec![]);
+        let instructions: Vec<&Instruction> = preceding.iter().chain([&claim]).collect();
+        let borrowed: Vec<_> = instructions
+            .iter()
+            .map(|ix| sysvar_instructions::BorrowedInstruction {
+                program_id: &ix.program_id,
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|meta| sysvar_instructions::BorrowedAccountMeta {
+                        pubkey: &meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect(),
+                data: &ix.data,
+            })
+            .collect();
+        let mut data = sysvar_instructions::construct_instructions_data(&borrowed);
+        sysvar_instructions::store_current_index(&mut data, preceding.len() as u16);
+
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &sysvar_instructions::ID,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &sysvar::ID,
+            false,
+            0,
+        );
+        verify_attestation(&account, attestor, message)
+    }
+
+    fn claim_message(amount: u64) -> Vec<u8> {
+        attestation_message(&Pubkey::new_unique(), &Pubkey::new_unique(), amount, 1_700_000_000)
+    }
+
+    #[test]
+    fn accepts_the_attestor_signing_the_claim() {
+        let attestor = Keypair::new();
+        let message = claim_message(1_000);
+        let ix = ed25519_instruction(&attestor, &message);
+        verify(&[ix], &attestor.pubkey(), &message).unwrap();
+    }
+
+    #[test]
+    fn rejects_another_signer() {
+        let attestor = Keypair::new();
+        let message = claim_message(1_000);
+        let ix = ed25519_instruction(&Keypair::new(), &message);
+        assert_eq!(
+            verify(&[ix], &attestor.pubkey(), &message).unwrap_err(),
+            ErrorCode::InvalidAttestation.into()
+        );
+    }
+
+    #[test]
+    fn rejects_a_signature_over_another_message() {
+        let attestor = Keypair::new();
+        let message = claim_message(1_000);
+        let mut inflated = message.clone();
+        inflated[64..72].copy_from_slice(&1_000_000u64.to_le_bytes());
+        let ix = ed25519_instruction(&attestor, &inflated);
+        assert_eq!(
+            verify(&[ix], &attestor.pubkey(), &message).unwrap_err(),
+            ErrorCode::InvalidAttestation.into()
+        );
+        // A signed prefix of the claim does not cover it either
+        let ix = ed25519_instruction(&attestor, &message[..64]);
+        assert_eq!(
+            verify(&[ix], &attestor.pubkey(), &message).unwrap_err(),
+            ErrorCode::InvalidAttestation.into()
+        );
+    }
+
+    #[test]
+    fn rejects_offsets_into_another_instruction() {
+        let attestor = Keypair::new();
+        let message = claim_message(1_000);
+        // Signature, public key and message are each read from instruction 0 instead
+        for field in [1, 3, 6] {
+            let mut ix = ed25519_instruction(&attestor, &message);
+            set_offset(&mut ix, field, 0);
+            assert_eq!(
+                verify(&[ix], &attestor.pubkey(), &message).unwrap_err(),
+                ErrorCode::InvalidAttestation.into()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_anything_but_a_single_signature() {
+        let attestor = Keypair::new();
+        let message = claim_message(1_000);
+        for num_signatures in [0, 2] {
+            let mut ix = ed25519_instruction(&attestor, &message);
+            ix.data[0] = num_signatures;
+            assert_eq!(
+                verify(&[ix], &attestor.pubkey(), &message).unwrap_err(),
+                ErrorCode::InvalidAttestation.into()
+            );
+        }
+    }
+
+    #[test]
+    fn requires_the_attestation_right_before_the_claim() {
+        let attestor = Keypair::new();
+        let message = claim_message(1_000);
+        assert_eq!(
+            verify(&[], &attestor.pubkey(), &message).unwrap_err(),
+            ErrorCode::MissingAttestation.into()
+        );
+        let ix = ed25519_instruction(&attestor, &message);
+        let other = Instruction::new_with_bytes(crate::ID, &[], vec![]);
+        assert_eq!(
+            verify(&[ix, other], &attestor.pubkey(), &message).unwrap_err(),
+            ErrorCode::MissingAttestation.into()
+        );
+    }
+}
@@ -0,0 +1,422 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+
+declare_id!("8LZtzyB6CykPftfeLsBYZ7F5ufNhi1w1z7Z2wwEKTECG");
+
+/// Maximum number of owners of a multisig
+pub const MAX_OWNERS: usize = 10;
+
+#[program]
+pub mod cat_multisig {
+    use super::*;
+
+    /// Create an M-of-N multisig. Its `multisig_signer` PDA is the key to set as
+    /// `authority` on `TokenInfo`, `AirdropInfo` or `PresaleInfo`.
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        owners: Vec<Pubkey>,
+        threshold: u64,
+    ) -> Result<()> {
+        require_valid_owners(&owners, threshold)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.creator = ctx.accounts.creator.key();
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        multisig.signer_bump = ctx.bumps.multisig_signer;
+        multisig.owner_set_seqno = 0;
+        multisig.transaction_count = 0;
+
+        emit!(MultisigCreated {
+            multisig: multisig.key(),
+            multisig_signer: ctx.accounts.multisig_signer.key(),
+            creator: multisig.creator,
+            owners: multisig.owners.clone(),
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Propose an instruction for the multisig signer to execute (only owners).
+    /// The proposer's approval is recorded right away.
+    pub fn create_transaction(
+        ctx: Context<CreateTransaction>,
+        program_id: Pubkey,
+        accounts: Vec<TransactionAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let owner_index = multisig
+            .owner_index(&ctx.accounts.proposer.key())
+            .ok_or(ErrorCode::NotAnOwner)?;
+
+        let mut signers = vec![false; multisig.owners.len()];
+        signers[owner_index] = true;
+
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.multisig = multisig.key();
+        transaction.index = multisig.transaction_count;
+        transaction.proposer = ctx.accounts.proposer.key();
+        transaction.program_id = program_id;
+        transaction.accounts = accounts;
+        transaction.data = data;
+        transaction.signers = signers;
+        transaction.did_execute = false;
+        transaction.owner_set_seqno = multisig.owner_set_seqno;
+
+        multisig.transaction_count += 1;
+
+        emit!(TransactionProposed {
+            multisig: transaction.multisig,
+            transaction: transaction.key(),
+            index: transaction.index,
+            proposer: transaction.proposer,
+            program_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Approve a pending transaction (only owners)
+    pub fn approve(
+        ctx: Context<Approve>,
+    ) -> Result<()> {
+        let owner_index = ctx
+            .accounts
+            .multisig
+            .owner_index(&ctx.accounts.owner.key())
+            .ok_or(ErrorCode::NotAnOwner)?;
+
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.signers[owner_index] = true;
+
+        emit!(TransactionApproved {
+            multisig: transaction.multisig,
+            transaction: transaction.key(),
+            index: transaction.index,
+            owner: ctx.accounts.owner.key(),
+            approvals: transaction.approvals(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Execute a transaction once it has `threshold` approvals (anyone can call).
+    ///
+    /// `remaining_accounts` must carry every account of the wrapped instruction,
+    /// plus the program being invoked.
+    pub fn execute_transaction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteTransaction<'info>>,
+    ) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let transaction = &mut ctx.accounts.transaction;
+
+        require!(!transaction.did_execute, ErrorCode::AlreadyExecuted);
+        require!(
+            transaction.approvals() >= multisig.threshold,
+            ErrorCode::NotEnoughApprovals
+        );
+        transaction.did_execute = true;
+
+        // The multisig signer is the only account that signs on behalf of the owners
+        let multisig_signer = ctx.accounts.multisig_signer.key();
+        let instruction = Instruction {
+            program_id: transaction.program_id,
+            accounts: transaction
+                .accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.pubkey == multisig_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: transaction.data.clone(),
+        };
+
+        let multisig_key = multisig.key();
+        let seeds = &[
+            b"multisig_signer".as_ref(),
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        invoke_signed(&instruction, ctx.remaining_accounts, signer)?;
+
+        emit!(TransactionExecuted {
+            multisig: multisig_key,
+            transaction: transaction.key(),
+            index: transaction.index,
+            program_id: transaction.program_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Replace the owners and threshold. Only callable by the multisig signer itself,
+    /// i.e. through an executed transaction. Pending transactions become void.
+    pub fn set_owners_and_threshold(
+        ctx: Context<Auth>,
+        owners: Vec<Pubkey>,
+        threshold: u64,
+    ) -> Result<()> {
+        require_valid_owners(&owners, threshold)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        multisig.owner_set_seqno += 1;
+
+        emit!(MultisigOwnersUpdated {
+            multisig: multisig.key(),
+            owners: multisig.owners.clone(),
+            threshold,
+            owner_set_seqno: multisig.owner_set_seqno,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+/// Owners must be unique, non-empty and able to reach the threshold
+fn require_valid_owners(owners: &[Pubkey], threshold: u64) -> Result<()> {
+    require!(
+        !owners.is_empty() && owners.len() <= MAX_OWNERS,
+        ErrorCode::InvalidOwners
+    );
+    for (index, owner) in owners.iter().enumerate() {
+        require!(!owners[..index].contains(owner), ErrorCode::InvalidOwners);
+    }
+    require!(
+        threshold > 0 && threshold <= owners.len() as u64,
+        ErrorCode::InvalidThreshold
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Multisig::INIT_SPACE,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: PDA that signs executed transactions, holds no data
+    #[account(
+        seeds = [b"multisig_signer", multisig.key().as_ref()],
+        bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(program_id: Pubkey, accounts: Vec<TransactionAccount>, data: Vec<u8>)]
+pub struct CreateTransaction<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Transaction::space(accounts.len(), data.len(), multisig.owners.len()),
+        seeds = [
+            b"transaction",
+            multisig.key().as_ref(),
+            multisig.transaction_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transaction: Account<'info, Transaction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Approve<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = multisig.owner_set_seqno == transaction.owner_set_seqno @ ErrorCode::OwnersChanged
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = !transaction.did_execute @ ErrorCode::AlreadyExecuted
+    )]
+    pub transaction: Account<'info, Transaction>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteTransaction<'info> {
+    #[account(
+        constraint = multisig.owner_set_seqno == transaction.owner_set_seqno @ ErrorCode::OwnersChanged
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: PDA that signs the wrapped instruction
+    #[account(
+        seeds = [b"multisig_signer", multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = multisig
+    )]
+    pub transaction: Account<'info, Transaction>,
+}
+
+#[derive(Accounts)]
+pub struct Auth<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        seeds = [b"multisig_signer", multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+    pub creator: Pubkey,
+    #[max_len(MAX_OWNERS)]
+    pub owners: Vec<Pubkey>,
+    pub threshold: u64,
+    pub signer_bump: u8,
+    pub owner_set_seqno: u32, // Bumped on every owner change, voids pending transactions
+    pub transaction_count: u64,
+}
+
+impl Multisig {
+    pub fn owner_index(&self, key: &Pubkey) -> Option<usize> {
+        self.owners.iter().position(|owner| owner == key)
+    }
+}
+
+#[account]
+pub struct Transaction {
+    pub multisig: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<TransactionAccount>,
+    pub data: Vec<u8>,
+    pub signers: Vec<bool>, // Approval per owner, indexed like `Multisig::owners`
+    pub did_execute: bool,
+    pub owner_set_seqno: u32,
+}
+
+impl Transaction {
+    /// Account size for a transaction with the given number of accounts, data bytes and owners
+    pub fn space(accounts: usize, data_len: usize, owners: usize) -> usize {
+        8 // discriminator
+            + 32 // multisig
+            + 8 // index
+            + 32 // proposer
+            + 32 // program_id
+            + 4 + accounts * TransactionAccount::INIT_SPACE
+            + 4 + data_len
+            + 4 + owners // signers
+            + 1 // did_execute
+            + 4 // owner_set_seqno
+    }
+
+    pub fn approvals(&self) -> u64 {
+        self.signers.iter().filter(|signed| **signed).count() as u64
+    }
+}
+
+/// Account meta of the wrapped instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+#[event]
+pub struct MultisigCreated {
+    pub multisig: Pubkey,
+    pub multisig_signer: Pubkey,
+    pub creator: Pubkey,
+    pub owners: Vec<Pubkey>,
+    pub threshold: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TransactionProposed {
+    pub multisig: Pubkey,
+    pub transaction: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub program_id: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TransactionApproved {
+    pub multisig: Pubkey,
+    pub transaction: Pubkey,
+    pub index: u64,
+    pub owner: Pubkey,
+    pub approvals: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TransactionExecuted {
+    pub multisig: Pubkey,
+    pub transaction: Pubkey,
+    pub index: u64,
+    pub program_id: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultisigOwnersUpdated {
+    pub multisig: Pubkey,
+    pub owners: Vec<Pubkey>,
+    pub threshold: u64,
+    pub owner_set_seqno: u32,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Owners must be unique and at most MAX_OWNERS")]
+    InvalidOwners,
+    #[msg("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold,
+    #[msg("Signer is not an owner of this multisig")]
+    NotAnOwner,
+    #[msg("Not enough owners approved the transaction")]
+    NotEnoughApprovals,
+    #[msg("Transaction has already been executed")]
+    AlreadyExecuted,
+    #[msg("Owners changed since the transaction was proposed")]
+    OwnersChanged,
+}
@@ -0,0 +1,347 @@
+//! `cat_multisig` against an in-process validator (`solana-program-test`).
+//!
+//! Run with `cargo test -p cat-multisig`.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::TransactionError;
+
+use cat_multisig::{accounts, instruction as args, ErrorCode, Multisig, Transaction as MultisigTransaction};
+use cat_multisig::{TransactionAccount, ID};
+
+/// `processor!` wants a plain `fn`, Anchor's `entry` ties the account slice to `'info`
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    cat_multisig::entry(program_id, accounts, data)
+}
+
+/// `cat_presale`, whose authority the multisig takes over
+fn process_presale_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    cat_presale::entry(program_id, accounts, data)
+}
+
+struct Setup {
+    context: ProgramTestContext,
+    owners: Vec<Keypair>,
+    multisig: Pubkey,
+    multisig_signer: Pubkey,
+}
+
+/// Create a 2-of-3 multisig with funded owners
+async fn setup() -> Setup {
+    let mut program = ProgramTest::new("cat_multisig", ID, processor!(process_instruction));
+    program.add_program("cat_presale", cat_presale::ID, processor!(process_presale_instruction));
+    let mut context = program.start_with_context().await;
+    let owners: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+
+    let funding: Vec<Instruction> = owners
+        .iter()
+        .map(|owner| system_instruction::transfer(&context.payer.pubkey(), &owner.pubkey(), 1_000_000_000))
+        .collect();
+    let payer = context.payer.insecure_clone();
+    send(&mut context, &funding, &[&payer]).await.unwrap();
+
+    let multisig = Keypair::new();
+    let multisig_signer =
+        Pubkey::find_program_address(&[b"multisig_signer", multisig.pubkey().as_ref()], &ID).0;
+    let create = instruction(
+        accounts::CreateMultisig {
+            creator: payer.pubkey(),
+            multisig: multisig.pubkey(),
+            multisig_signer,
+            system_program: system_program::ID,
+        },
+        args::CreateMultisig {
+            owners: owners.iter().map(|owner| owner.pubkey()).collect(),
+            threshold: 2,
+        },
+    );
+    send(&mut context, &[create], &[&payer, &multisig]).await.unwrap();
+
+    Setup { context, owners, multisig: multisig.pubkey(), multisig_signer }
+}
+
+fn instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+fn presale_instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: cat_presale::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> std::result::Result<(), BanksClientError> {
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = solana_sdk::transaction::Transaction::new_signed_with_payer(
+        instructions,
+        Some(&signers[0].pubkey()),
+        signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn account<T: AccountDeserialize>(context: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+fn assert_error(result: std::result::Result<(), BanksClientError>, error: impl Into<u32> + std::fmt::Debug) {
+    let expected = format!("{error:?}");
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, error.into(), "expected {expected}")
+        }
+        other => panic!("expected {expected}, got {other:?}"),
+    }
+}
+
+impl Setup {
+    fn transaction_address(&self, index: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"transaction", self.multisig.as_ref(), index.to_le_bytes().as_ref()],
+            &ID,
+        )
+        .0
+    }
+
+    /// Propose `wrapped`, to be signed by `multisig_signer` once executed
+    async fn propose(&mut self, proposer: usize, wrapped: Instruction) -> Pubkey {
+        let index = account::<Multisig>(&mut self.context, self.multisig).await.transaction_count;
+        let transaction = self.transaction_address(index);
+        let propose = instruction(
+            accounts::CreateTransaction {
+                proposer: self.owners[proposer].pubkey(),
+                multisig: self.multisig,
+                transaction,
+                system_program: system_program::ID,
+            },
+            args::CreateTransaction {
+                program_id: wrapped.program_id,
+                accounts: wrapped
+                    .accounts
+                    .into_iter()
+                    .map(|meta| TransactionAccount { pubkey: meta.pubkey, is_writable: meta.is_writable })
+                    .collect(),
+                data: wrapped.data,
+            },
+        );
+        let proposer = self.owners[proposer].insecure_clone();
+        send(&mut self.context, &[propose], &[&proposer]).await.unwrap();
+        transaction
+    }
+
+    /// Propose `set_owners_and_threshold` on the multisig itself
+    async fn propose_owner_change(&mut self, proposer: usize, owners: Vec<Pubkey>, threshold: u64) -> Pubkey {
+        let wrapped = instruction(
+            accounts::Auth { multisig: self.multisig, multisig_signer: self.multisig_signer },
+            args::SetOwnersAndThreshold { owners, threshold },
+        );
+        self.propose(proposer, wrapped).await
+    }
+
+    async fn approve(&mut self, owner: usize, transaction: Pubkey) -> std::result::Result<(), BanksClientError> {
+        let approve = instruction(
+            accounts::Approve { owner: self.owners[owner].pubkey(), multisig: self.multisig, transaction },
+            args::Approve {},
+        );
+        let owner = self.owners[owner].insecure_clone();
+        send(&mut self.context, &[approve], &[&owner]).await
+    }
+
+    async fn execute(&mut self, transaction: Pubkey) -> std::result::Result<(), BanksClientError> {
+        let mut execute = instruction(
+            accounts::ExecuteTransaction {
+                multisig: self.multisig,
+                multisig_signer: self.multisig_signer,
+                transaction,
+            },
+            args::ExecuteTransaction {},
+        );
+        // Accounts of the wrapped instruction, then the program it invokes.
+        // `multisig_signer` only signs through `invoke_signed`, never on the outer transaction
+        let wrapped = account::<MultisigTransaction>(&mut self.context, transaction).await;
+        execute.accounts.extend(wrapped.accounts.iter().map(|account| AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: false,
+            is_writable: account.is_writable,
+        }));
+        execute.accounts.push(AccountMeta::new_readonly(wrapped.program_id, false));
+        // A retried execute is byte-for-byte the same transaction, which the bank answers
+        // from its status cache with the earlier result unless the blockhash has moved on
+        self.context.get_new_latest_blockhash().await.unwrap();
+        let payer = self.context.payer.insecure_clone();
+        send(&mut self.context, &[execute], &[&payer]).await
+    }
+}
+
+#[tokio::test]
+async fn create_multisig_records_owners_and_threshold() {
+    let mut setup = setup().await;
+    let multisig = account::<Multisig>(&mut setup.context, setup.multisig).await;
+
+    assert_eq!(
+        multisig.owners,
+        setup.owners.iter().map(|owner| owner.pubkey()).collect::<Vec<_>>()
+    );
+    assert_eq!(multisig.threshold, 2);
+    assert_eq!(multisig.owner_set_seqno, 0);
+    assert_eq!(multisig.transaction_count, 0);
+}
+
+#[tokio::test]
+async fn execution_waits_for_the_threshold() {
+    let mut setup = setup().await;
+    let new_owners = vec![setup.owners[0].pubkey(), setup.owners[1].pubkey()];
+    let transaction = setup.propose_owner_change(0, new_owners.clone(), 1).await;
+
+    // The proposer's approval alone is below the 2-of-3 threshold
+    let proposed = account::<MultisigTransaction>(&mut setup.context, transaction).await;
+    assert_eq!(proposed.approvals(), 1);
+    let result = setup.execute(transaction).await;
+    assert_error(result, ErrorCode::NotEnoughApprovals);
+
+    // Outsiders cannot approve
+    let outsider = Keypair::new();
+    let approve = instruction(
+        accounts::Approve { owner: outsider.pubkey(), multisig: setup.multisig, transaction },
+        args::Approve {},
+    );
+    let payer = setup.context.payer.insecure_clone();
+    let result = send(&mut setup.context, &[approve], &[&payer, &outsider]).await;
+    assert_error(result, ErrorCode::NotAnOwner);
+
+    setup.approve(1, transaction).await.unwrap();
+    setup.execute(transaction).await.unwrap();
+
+    let multisig = account::<Multisig>(&mut setup.context, setup.multisig).await;
+    assert_eq!(multisig.owners, new_owners);
+    assert_eq!(multisig.threshold, 1);
+    assert_eq!(multisig.owner_set_seqno, 1);
+    assert!(account::<MultisigTransaction>(&mut setup.context, transaction).await.did_execute);
+
+    // Executing bumped the owner set, so the change itself cannot be approved again
+    let result = setup.approve(0, transaction).await;
+    assert_error(result, ErrorCode::OwnersChanged);
+}
+
+#[tokio::test]
+async fn owner_change_voids_pending_transactions() {
+    let mut setup = setup().await;
+    let owners: Vec<Pubkey> = setup.owners.iter().map(|owner| owner.pubkey()).collect();
+    let pending = setup.propose_owner_change(2, owners.clone(), 3).await;
+    setup.approve(1, pending).await.unwrap();
+    let change = setup.propose_owner_change(0, owners, 2).await;
+
+    setup.approve(1, change).await.unwrap();
+    setup.execute(change).await.unwrap();
+
+    // `pending` was proposed under owner set 0 and is void now, even with enough approvals
+    let result = setup.approve(0, pending).await;
+    assert_error(result, ErrorCode::OwnersChanged);
+    let result = setup.execute(pending).await;
+    assert_error(result, ErrorCode::OwnersChanged);
+}
+
+#[tokio::test]
+async fn multisig_takes_over_the_presale_authority() {
+    let mut setup = setup().await;
+    let authority = setup.context.payer.insecure_clone();
+    let presale_info = Pubkey::find_program_address(&[b"presale_info"], &cat_presale::ID).0;
+
+    // CAT mint and a presale owned by a single-key authority
+    let mint = Keypair::new();
+    let rent = setup.context.banks_client.get_rent().await.unwrap();
+    let create_mint = [
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &authority.pubkey(), None, 6)
+            .unwrap(),
+    ];
+    send(&mut setup.context, &create_mint, &[&authority, &mint]).await.unwrap();
+    let initialize = presale_instruction(
+        cat_presale::accounts::InitializePresale {
+            authority: authority.pubkey(),
+            presale_info,
+            token_mint: mint.pubkey(),
+            token_vault: Pubkey::find_program_address(&[b"token_vault"], &cat_presale::ID).0,
+            sol_vault: Pubkey::find_program_address(&[b"sol_vault"], &cat_presale::ID).0,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        cat_presale::instruction::InitializePresale {
+            token_price: 400,
+            total_tokens_for_sale: 1_000_000_000_000,
+            min_purchase: 1,
+            max_purchase: u64::MAX,
+            start_time: i64::MAX - 1,
+            end_time: i64::MAX,
+            soft_cap: 0,
+            hard_cap_lamports: u64::MAX,
+        },
+    );
+    let propose_authority = presale_instruction(
+        cat_presale::accounts::UpdatePresale { authority: authority.pubkey(), presale_info },
+        cat_presale::instruction::ProposeAuthority { new_authority: setup.multisig_signer },
+    );
+    send(&mut setup.context, &[initialize, propose_authority], &[&authority]).await.unwrap();
+
+    // The multisig accepts through a 2-of-3 transaction
+    let accept = presale_instruction(
+        cat_presale::accounts::AcceptPresaleAuthority { new_authority: setup.multisig_signer, presale_info },
+        cat_presale::instruction::AcceptAuthority {},
+    );
+    let transaction = setup.propose(0, accept).await;
+    setup.approve(1, transaction).await.unwrap();
+    setup.execute(transaction).await.unwrap();
+
+    let presale = account::<cat_presale::PresaleInfo>(&mut setup.context, presale_info).await;
+    assert_eq!(presale.authority, setup.multisig_signer);
+    assert_eq!(presale.pending_authority, Pubkey::default());
+
+    // The previous key has lost its admin rights
+    let pause = |authority: Pubkey| {
+        presale_instruction(
+            cat_presale::accounts::UpdatePresale { authority, presale_info },
+            cat_presale::instruction::PausePresale {},
+        )
+    };
+    let result = send(&mut setup.context, &[pause(authority.pubkey())], &[&authority]).await;
+    assert_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+
+    // Admin calls now go through the multisig, and only once the threshold is met
+    let transaction = setup.propose(2, pause(setup.multisig_signer)).await;
+    let result = setup.execute(transaction).await;
+    assert_error(result, ErrorCode::NotEnoughApprovals);
+    assert!(account::<cat_presale::PresaleInfo>(&mut setup.context, presale_info).await.is_active);
+
+    setup.approve(0, transaction).await.unwrap();
+    setup.execute(transaction).await.unwrap();
+    assert!(!account::<cat_presale::PresaleInfo>(&mut setup.context, presale_info).await.is_active);
+}
+
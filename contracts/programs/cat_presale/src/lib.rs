@@ -0,0 +1,3570 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use cat_merkle as merkle;
+
+declare_id!("Cc1Jx5ehpbSp9aHDf15r196GKuo1Fd7eQuT6XwABg3Pi");
+
+/// Maximum number of sale rounds (seed, private, public, ...)
+pub const MAX_ROUNDS: usize = 5;
+/// Maximum number of SPL tokens (USDC, USDT, ...) accepted as payment
+pub const MAX_PAYMENT_MINTS: usize = 4;
+/// Maximum number of wallets raise proceeds are split between
+pub const MAX_PAYEES: usize = 5;
+/// Delay before a queued settings change can be executed, unless changed through the timelock itself
+pub const DEFAULT_TIMELOCK_DELAY: i64 = 24 * 60 * 60;
+/// Longest timelock delay a queued update can set
+pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+/// 100% expressed in basis points
+pub const BPS_DENOMINATOR: u16 = 10_000;
+/// Latest a TGE can be scheduled after the sale ends
+pub const MAX_TGE_DELAY: i64 = 365 * 24 * 60 * 60;
+/// Longest post-TGE vesting `set_tge` accepts
+pub const MAX_VESTING_DURATION: i64 = 4 * 365 * 24 * 60 * 60;
+
+#[program]
+pub mod cat_presale {
+    use super::*;
+
+    /// Initialize the presale program
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_presale(
+        ctx: Context<InitializePresale>,
+        token_price: u64, // Price in lamports per whole CAT token (10^decimals base units)
+        total_tokens_for_sale: u64,
+        min_purchase: u64,
+        max_purchase: u64,
+        start_time: i64,
+        end_time: i64,
+        soft_cap: u64, // Minimum lamports raised for the sale to succeed
+        hard_cap_lamports: u64, // Maximum lamports raised before the sale sells out
+    ) -> Result<()> {
+        require!(token_price > 0, ErrorCode::InvalidTokenPrice);
+        require!(hard_cap_lamports >= soft_cap, ErrorCode::InvalidCaps);
+
+        let presale_info = &mut ctx.accounts.presale_info;
+        presale_info.authority = ctx.accounts.authority.key();
+        presale_info.token_mint = ctx.accounts.token_mint.key();
+        presale_info.token_decimals = ctx.accounts.token_mint.decimals;
+        presale_info.token_vault = ctx.accounts.token_vault.key();
+        presale_info.sol_vault = ctx.accounts.sol_vault.key();
+        presale_info.token_price = token_price; // 400 lamports per CAT token (1 SOL = 2.5M CAT)
+        presale_info.total_tokens_for_sale = total_tokens_for_sale;
+        presale_info.tokens_sold = 0;
+        presale_info.sol_raised = 0;
+        presale_info.payment_sol_value = 0;
+        presale_info.total_buyers = 0;
+        presale_info.min_purchase = min_purchase;
+        presale_info.max_purchase = max_purchase;
+        presale_info.start_time = start_time;
+        presale_info.end_time = end_time;
+        presale_info.is_active = true;
+        presale_info.closed = false;
+        presale_info.split_across_rounds = false;
+        presale_info.rounds = Vec::new();
+        presale_info.payment_mints = Vec::new();
+        presale_info.payees = Vec::new();
+        presale_info.soft_cap = soft_cap;
+        presale_info.hard_cap_lamports = hard_cap_lamports;
+        presale_info.deferred_delivery = false;
+        presale_info.tokens_owed = 0;
+        presale_info.tge_time = 0;
+        presale_info.tge_unlock_bps = 0;
+        presale_info.vesting_duration = 0;
+        presale_info.fair_launch = false;
+        presale_info.total_deposits = 0;
+        presale_info.settled_deposits = 0;
+        presale_info.fair_launch_sol_cap = 0;
+        presale_info.fair_launch_reserved = 0;
+        presale_info.dutch_auction = false;
+        presale_info.auction_start_price = 0;
+        presale_info.auction_floor_price = 0;
+        presale_info.auction_step_interval = 0;
+        presale_info.auction_lowest_price = 0;
+        presale_info.auction_clearing_price = 0;
+        presale_info.auction_refunds_reserved = 0;
+        presale_info.auction_refunds_settled = 0;
+        presale_info.referral_bps = 0;
+        presale_info.referral_rewards_owed = 0;
+        presale_info.pending_authority = Pubkey::default();
+        presale_info.timelock_delay = DEFAULT_TIMELOCK_DELAY;
+        presale_info.pending_update = None;
+        presale_info.pending_update_eta = 0;
+        presale_info.status = PresaleStatus::Active;
+
+        let sol_vault = &mut ctx.accounts.sol_vault;
+        sol_vault.raised = 0;
+        sol_vault.withdrawn = 0;
+        sol_vault.refunded = 0;
+
+        emit!(PresaleInitialized {
+            authority: presale_info.authority,
+            token_mint: presale_info.token_mint,
+            token_price,
+            total_tokens_for_sale,
+            min_purchase,
+            max_purchase,
+            start_time,
+            end_time,
+            soft_cap,
+            hard_cap_lamports,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Buy tokens in presale, returning the CAT base units filled
+    pub fn buy_tokens(
+        ctx: Context<BuyTokens>,
+        sol_amount: u64, // Amount of SOL to spend (in lamports)
+        min_tokens_out: u64, // Fail if fewer CAT base units would be filled
+        max_price: Option<u64>, // Fail if any fill is priced above this (lamports per CAT)
+        whitelist_proof: Option<WhitelistProof>, // Needed on the first purchase in a whitelisted round
+    ) -> Result<u64> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let purchase_record = &mut ctx.accounts.purchase_record;
+        let clock = Clock::get()?;
+
+        // Check if presale is active
+        require!(
+            presale_info.is_active && presale_info.status == PresaleStatus::Active,
+            ErrorCode::PresaleNotActive
+        );
+        require!(!presale_info.fair_launch, ErrorCode::FairLaunchDepositsOnly);
+
+        // Check time bounds
+        require!(
+            clock.unix_timestamp >= presale_info.start_time,
+            ErrorCode::PresaleNotStarted
+        );
+        require!(
+            clock.unix_timestamp <= presale_info.end_time,
+            ErrorCode::PresaleEnded
+        );
+
+        // Only take what still fits under the hard cap, the excess never leaves the buyer
+        let requested_sol = sol_amount;
+        let sol_amount = requested_sol.min(presale_info.hard_cap_remaining());
+        let partially_filled = sol_amount < requested_sol;
+
+        // Price the purchase in the active round(s), rounded down
+        let fills = presale_info.plan_purchase(clock.unix_timestamp, sol_amount)?;
+        let token_amount: u64 = fills.iter().map(|fill| fill.token_amount).sum();
+
+        // Slippage protection against price changes between quote and execution
+        if let Some(max_price) = max_price {
+            for fill in &fills {
+                require!(fill.token_price <= max_price, ErrorCode::PriceAboveMaximum);
+            }
+        }
+        require!(token_amount >= min_tokens_out, ErrorCode::SlippageExceeded);
+
+        // Credited and vested tokens only unlock at TGE, which cannot be scheduled once the sale runs
+        let unlocks_at_tge = presale_info.deferred_delivery
+            || fills
+                .iter()
+                .any(|fill| matches!(fill.round, Some(index) if presale_info.rounds[index].is_vested()));
+        require!(
+            !unlocks_at_tge || presale_info.tge_time > 0,
+            ErrorCode::TgeNotScheduled
+        );
+
+        // Check minimum and maximum purchase limits, a fill that closes out the cap may be smaller
+        let min_purchase = match fills[0].round {
+            Some(index) => presale_info.rounds[index].min_purchase,
+            None => presale_info.min_purchase,
+        };
+        require!(
+            token_amount > 0 && (token_amount >= min_purchase || partially_filled),
+            ErrorCode::BelowMinimumPurchase
+        );
+
+        // Whitelisted rounds use the wallet's personal allocation instead of the public limits
+        let whitelisted_amount: u64 = fills
+            .iter()
+            .filter(|fill| matches!(fill.round, Some(index) if presale_info.rounds[index].is_whitelisted()))
+            .map(|fill| fill.token_amount)
+            .sum();
+        let public_purchased =
+            purchase_record.total_purchased - purchase_record.whitelisted_purchased(&presale_info.rounds);
+        require!(
+            public_purchased + token_amount - whitelisted_amount <= presale_info.max_purchase,
+            ErrorCode::ExceedsMaximumPurchase
+        );
+        for fill in &fills {
+            if let Some(index) = fill.round {
+                let round = &presale_info.rounds[index];
+                let round_cap = if round.is_whitelisted() {
+                    // The proof is checked once, the allocation it proves is kept on the record
+                    if purchase_record.round_allocation[index] == 0 {
+                        let whitelist_proof = whitelist_proof.as_ref().ok_or(ErrorCode::NotWhitelisted)?;
+                        let leaf = merkle::leaf_hash(&ctx.accounts.buyer.key(), whitelist_proof.max_allocation);
+                        require!(
+                            merkle::verify(
+                                &whitelist_proof.proof,
+                                &round.merkle_root,
+                                leaf,
+                                whitelist_proof.index
+                            ),
+                            ErrorCode::NotWhitelisted
+                        );
+                        purchase_record.round_allocation[index] = whitelist_proof.max_allocation;
+                    }
+                    purchase_record.round_allocation[index]
+                } else {
+                    round.max_purchase
+                };
+                require!(
+                    purchase_record.round_purchased[index] + fill.token_amount <= round_cap,
+                    ErrorCode::ExceedsMaximumPurchase
+                );
+            }
+        }
+
+        // Check if there are enough tokens left, the referrer's bonus included
+        let referral_reward = if ctx.accounts.referral_record.is_some() {
+            (token_amount as u128 * presale_info.referral_bps as u128 / BPS_DENOMINATOR as u128) as u64
+        } else {
+            0
+        };
+        require!(
+            presale_info.tokens_committed() + token_amount + referral_reward
+                <= presale_info.total_tokens_for_sale,
+            ErrorCode::InsufficientTokensForSale
+        );
+
+        // Check if user has enough SOL
+        require!(
+            ctx.accounts.buyer.lamports() >= sol_amount,
+            ErrorCode::InsufficientSOL
+        );
+
+        // Transfer SOL from buyer to sol_vault
+        let ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.buyer.key(),
+            &ctx.accounts.sol_vault.key(),
+            sol_amount,
+        );
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[
+                ctx.accounts.buyer.to_account_info(),
+                ctx.accounts.sol_vault.to_account_info(),
+            ],
+        )?;
+        ctx.accounts.sol_vault.raised += sol_amount;
+
+        // Tokens bought in vested rounds stay in the vault until `release_vested`
+        let locked_amount: u64 = fills
+            .iter()
+            .filter(|fill| matches!(fill.round, Some(index) if presale_info.rounds[index].is_vested()))
+            .map(|fill| fill.token_amount)
+            .sum();
+        let liquid_amount = token_amount - locked_amount;
+
+        // Transfer tokens from vault to buyer, or credit them for claiming after TGE
+        if presale_info.deferred_delivery {
+            presale_info.tokens_owed += token_amount;
+        } else {
+            presale_info.tokens_owed += locked_amount;
+        }
+        if !presale_info.deferred_delivery && liquid_amount > 0 {
+            let seeds = &[
+                b"presale_info".as_ref(),
+                &[ctx.bumps.presale_info],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.token_vault.to_account_info(),
+                to: ctx.accounts.buyer_token_account.to_account_info(),
+                authority: presale_info.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+            token::transfer(cpi_ctx, liquid_amount)?;
+            purchase_record.tokens_claimed += liquid_amount;
+        }
+
+        // Credit the referrer, who is fixed on the buyer's first purchase
+        if let Some(referral_record) = ctx.accounts.referral_record.as_mut() {
+            let referrer = referral_record.referrer;
+            if purchase_record.total_purchased == 0 {
+                require_keys_neq!(referrer, ctx.accounts.buyer.key(), ErrorCode::InvalidReferrer);
+                purchase_record.referrer = referrer;
+                referral_record.total_referrals += 1;
+            }
+            require_keys_eq!(purchase_record.referrer, referrer, ErrorCode::InvalidReferrer);
+
+            referral_record.total_referred_tokens += token_amount;
+            referral_record.total_referred_sol += sol_amount;
+            referral_record.rewards_earned += referral_reward;
+            presale_info.referral_rewards_owed += referral_reward;
+
+            emit!(ReferralCredited {
+                referrer,
+                buyer: ctx.accounts.buyer.key(),
+                token_amount,
+                sol_amount,
+                reward: referral_reward,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        // Update purchase record
+        if purchase_record.total_purchased == 0 {
+            purchase_record.buyer = ctx.accounts.buyer.key();
+            purchase_record.first_purchase_at = clock.unix_timestamp;
+            presale_info.total_buyers += 1;
+        }
+        purchase_record.total_purchased += token_amount;
+        purchase_record.total_sol_spent += sol_amount;
+        purchase_record.last_purchase_at = clock.unix_timestamp;
+
+        // Update presale and round stats
+        presale_info.tokens_sold += token_amount;
+        presale_info.sol_raised += sol_amount;
+        for fill in &fills {
+            if let Some(index) = fill.round {
+                purchase_record.round_purchased[index] += fill.token_amount;
+                presale_info.rounds[index].tokens_sold += fill.token_amount;
+                presale_info.rounds[index].sol_raised += fill.sol_amount;
+                emit!(RoundPurchased {
+                    round_index: index as u8,
+                    buyer: ctx.accounts.buyer.key(),
+                    token_amount: fill.token_amount,
+                    sol_amount: fill.sol_amount,
+                    token_price: fill.token_price,
+                    timestamp: clock.unix_timestamp,
+                });
+            }
+        }
+        if presale_info.dutch_auction {
+            presale_info.auction_lowest_price = presale_info.auction_lowest_price.min(fills[0].token_price);
+        }
+        presale_info.update_sold_out(clock.unix_timestamp);
+
+        emit!(TokensPurchased {
+            buyer: ctx.accounts.buyer.key(),
+            payment_mint: None,
+            payment_amount: sol_amount,
+            excess_not_taken: if partially_filled { requested_sol - sol_amount } else { 0 },
+            token_amount,
+            tokens_delivered: if presale_info.deferred_delivery { 0 } else { liquid_amount },
+            auction_price: presale_info.dutch_auction.then(|| fills[0].token_price),
+            tokens_sold: presale_info.tokens_sold,
+            sol_raised: presale_info.sol_raised,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(token_amount)
+    }
+
+    /// Buy tokens in presale, paying with an accepted SPL token.
+    ///
+    /// SPL payments are only taken at the flat price: sales with rounds (and their
+    /// whitelists and vesting) or a Dutch auction are SOL only. The purchase is valued
+    /// in SOL at `token_price` so that it counts towards the hard cap.
+    pub fn buy_tokens_with_token(
+        ctx: Context<BuyTokensWithToken>,
+        payment_amount: u64, // Amount of the payment token to spend (in its base units)
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let purchase_record = &mut ctx.accounts.purchase_record;
+        let clock = Clock::get()?;
+
+        // Check if presale is active
+        require!(
+            presale_info.is_active && presale_info.status == PresaleStatus::Active,
+            ErrorCode::PresaleNotActive
+        );
+        require!(!presale_info.fair_launch, ErrorCode::FairLaunchDepositsOnly);
+        require!(!presale_info.dutch_auction, ErrorCode::DutchAuctionSolOnly);
+        require!(presale_info.rounds.is_empty(), ErrorCode::RoundsSolOnly);
+
+        // Check time bounds
+        require!(
+            clock.unix_timestamp >= presale_info.start_time,
+            ErrorCode::PresaleNotStarted
+        );
+        require!(
+            clock.unix_timestamp <= presale_info.end_time,
+            ErrorCode::PresaleEnded
+        );
+
+        // Credited tokens only unlock at TGE, which cannot be scheduled once the sale runs
+        require!(
+            !presale_info.deferred_delivery || presale_info.tge_time > 0,
+            ErrorCode::TgeNotScheduled
+        );
+
+        let payment_index = presale_info
+            .payment_mints
+            .iter()
+            .position(|payment_mint| payment_mint.mint == ctx.accounts.payment_mint.key())
+            .ok_or(ErrorCode::PaymentMintNotAccepted)?;
+
+        // Same fixed-point math as SOL, with the price in payment base units per whole CAT
+        let token_amount = quote_tokens_for_sol(
+            payment_amount,
+            presale_info.payment_mints[payment_index].token_price,
+            presale_info.token_decimals,
+        )?;
+
+        // Check minimum and maximum purchase limits
+        require!(
+            token_amount > 0 && token_amount >= presale_info.min_purchase,
+            ErrorCode::BelowMinimumPurchase
+        );
+        require!(
+            purchase_record.total_purchased + token_amount <= presale_info.max_purchase,
+            ErrorCode::ExceedsMaximumPurchase
+        );
+
+        // Check if there are enough tokens left
+        require!(
+            presale_info.tokens_committed() + token_amount <= presale_info.total_tokens_for_sale,
+            ErrorCode::InsufficientTokensForSale
+        );
+
+        // The SOL the same tokens would have cost must still fit under the hard cap
+        let sol_value = quote_sol_for_tokens(
+            token_amount,
+            presale_info.token_price,
+            presale_info.token_decimals,
+        )?;
+        require!(sol_value <= presale_info.hard_cap_remaining(), ErrorCode::HardCapExceeded);
+
+        // Transfer payment tokens from buyer to the payment vault
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.buyer_payment_account.to_account_info(),
+            to: ctx.accounts.payment_vault.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::transfer(cpi_ctx, payment_amount)?;
+
+        // Transfer tokens from vault to buyer, or credit them for claiming after TGE
+        if presale_info.deferred_delivery {
+            presale_info.tokens_owed += token_amount;
+        } else {
+            let seeds = &[
+                b"presale_info".as_ref(),
+                &[ctx.bumps.presale_info],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.token_vault.to_account_info(),
+                to: ctx.accounts.buyer_token_account.to_account_info(),
+                authority: presale_info.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+            token::transfer(cpi_ctx, token_amount)?;
+            purchase_record.tokens_claimed += token_amount;
+        }
+
+        // Update purchase record
+        if purchase_record.total_purchased == 0 {
+            purchase_record.buyer = ctx.accounts.buyer.key();
+            purchase_record.first_purchase_at = clock.unix_timestamp;
+            presale_info.total_buyers += 1;
+        }
+        purchase_record.total_purchased += token_amount;
+        purchase_record.token_spent[payment_index] += payment_amount;
+        purchase_record.last_purchase_at = clock.unix_timestamp;
+
+        // Update presale stats
+        presale_info.tokens_sold += token_amount;
+        presale_info.payment_mints[payment_index].raised += payment_amount;
+        presale_info.payment_sol_value += sol_value;
+        presale_info.update_sold_out(clock.unix_timestamp);
+
+        emit!(TokensPurchased {
+            buyer: ctx.accounts.buyer.key(),
+            payment_mint: Some(ctx.accounts.payment_mint.key()),
+            payment_amount,
+            excess_not_taken: 0,
+            token_amount,
+            tokens_delivered: if presale_info.deferred_delivery { 0 } else { token_amount },
+            auction_price: None,
+            tokens_sold: presale_info.tokens_sold,
+            sol_raised: presale_info.sol_raised,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Quote what `buy_tokens` would take and deliver for `sol_amount` at the current price
+    pub fn quote_purchase(
+        ctx: Context<QuotePurchase>,
+        sol_amount: u64,
+    ) -> Result<PurchaseQuote> {
+        let presale_info = &ctx.accounts.presale_info;
+
+        // Same hard cap clamp as `buy_tokens`, the excess would never leave the buyer
+        let sol_amount = sol_amount.min(presale_info.hard_cap_remaining());
+        let fills = presale_info.plan_purchase(Clock::get()?.unix_timestamp, sol_amount)?;
+
+        Ok(PurchaseQuote {
+            token_amount: fills.iter().map(|fill| fill.token_amount).sum(),
+            sol_amount,
+        })
+    }
+
+    /// Register as a referrer (requires an earlier purchase of your own)
+    pub fn register_referrer(
+        ctx: Context<RegisterReferrer>,
+    ) -> Result<()> {
+        let referral_record = &mut ctx.accounts.referral_record;
+
+        // Referrers must have bought before anyone they refer, so referrals cannot form a cycle
+        require!(
+            ctx.accounts.purchase_record.total_purchased > 0,
+            ErrorCode::ReferrerHasNoPurchase
+        );
+
+        referral_record.referrer = ctx.accounts.referrer.key();
+        referral_record.total_referrals = 0;
+        referral_record.total_referred_tokens = 0;
+        referral_record.total_referred_sol = 0;
+        referral_record.rewards_earned = 0;
+        referral_record.rewards_claimed = 0;
+
+        emit!(ReferrerRegistered {
+            referrer: referral_record.referrer,
+            referral_record: referral_record.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Claim bonus CAT earned through referrals, once the sale has succeeded and any scheduled TGE has passed
+    pub fn claim_referral_rewards(
+        ctx: Context<ClaimReferralRewards>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let referral_record = &mut ctx.accounts.referral_record;
+        let clock = Clock::get()?;
+
+        require!(
+            presale_info.status == PresaleStatus::Succeeded,
+            ErrorCode::PresaleNotSucceeded
+        );
+        // Bonuses are no more liquid than the purchases they were earned on
+        require!(
+            presale_info.tge_time == 0 || clock.unix_timestamp >= presale_info.tge_time,
+            ErrorCode::TgeNotReached
+        );
+
+        let claimable = referral_record.rewards_earned - referral_record.rewards_claimed;
+        require!(claimable > 0, ErrorCode::NothingToClaim);
+
+        // Bonuses are paid from the same vault as the tokens still owed to buyers, which must stay covered
+        require!(
+            ctx.accounts.token_vault.amount
+                >= presale_info.tokens_owed + presale_info.fair_launch_reserved + presale_info.referral_rewards_owed,
+            ErrorCode::VaultBelowObligations
+        );
+
+        referral_record.rewards_claimed += claimable;
+        presale_info.referral_rewards_owed -= claimable;
+
+        let seeds = &[
+            b"presale_info".as_ref(),
+            &[ctx.bumps.presale_info],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.token_vault.to_account_info(),
+            to: ctx.accounts.referrer_token_account.to_account_info(),
+            authority: presale_info.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, claimable)?;
+
+        emit!(ReferralRewardsClaimed {
+            referrer: referral_record.referrer,
+            amount: claimable,
+            rewards_claimed: referral_record.rewards_claimed,
+            rewards_earned: referral_record.rewards_earned,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Report the `sol_vault` ledger and how much of its balance can be withdrawn
+    pub fn get_sol_vault_balance(
+        ctx: Context<GetSolVaultBalance>,
+    ) -> Result<SolVaultBalance> {
+        sol_vault_balance(&ctx.accounts.presale_info, &ctx.accounts.sol_vault)
+    }
+
+    /// Deposit SOL into an oversubscribed fair launch, tokens are allocated after the sale ends
+    pub fn deposit_sol(
+        ctx: Context<DepositSol>,
+        amount: u64, // Lamports to deposit
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let purchase_record = &mut ctx.accounts.purchase_record;
+        let clock = Clock::get()?;
+
+        require!(presale_info.fair_launch, ErrorCode::FairLaunchDisabled);
+        require!(
+            presale_info.is_active && presale_info.status == PresaleStatus::Active,
+            ErrorCode::PresaleNotActive
+        );
+        require!(
+            clock.unix_timestamp >= presale_info.start_time,
+            ErrorCode::PresaleNotStarted
+        );
+        require!(
+            clock.unix_timestamp <= presale_info.end_time,
+            ErrorCode::PresaleEnded
+        );
+
+        // Credited tokens only unlock at TGE, which cannot be scheduled once the sale runs
+        require!(
+            !presale_info.deferred_delivery || presale_info.tge_time > 0,
+            ErrorCode::TgeNotScheduled
+        );
+
+        require!(amount > 0, ErrorCode::BelowMinimumPurchase);
+
+        // Transfer SOL from depositor to sol_vault
+        let ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.buyer.key(),
+            &ctx.accounts.sol_vault.key(),
+            amount,
+        );
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[
+                ctx.accounts.buyer.to_account_info(),
+                ctx.accounts.sol_vault.to_account_info(),
+            ],
+        )?;
+        ctx.accounts.sol_vault.raised += amount;
+
+        if purchase_record.total_purchased == 0 && purchase_record.deposited == 0 {
+            purchase_record.buyer = ctx.accounts.buyer.key();
+            purchase_record.first_purchase_at = clock.unix_timestamp;
+            presale_info.total_buyers += 1;
+        }
+        purchase_record.deposited += amount;
+        purchase_record.last_purchase_at = clock.unix_timestamp;
+        presale_info.total_deposits += amount;
+
+        emit!(SolDeposited {
+            depositor: ctx.accounts.buyer.key(),
+            amount,
+            deposited: purchase_record.deposited,
+            total_deposits: presale_info.total_deposits,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Settle a fair launch deposit after a successful sale: deliver the pro-rata
+    /// allocation and refund the unused SOL. Anyone can settle on the depositor's behalf.
+    pub fn settle_deposit(
+        ctx: Context<SettleDeposit>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let purchase_record = &mut ctx.accounts.purchase_record;
+
+        require!(presale_info.fair_launch, ErrorCode::FairLaunchDisabled);
+        require!(
+            presale_info.status == PresaleStatus::Succeeded,
+            ErrorCode::PresaleNotSucceeded
+        );
+        require!(
+            purchase_record.deposited > 0 && !purchase_record.deposit_settled,
+            ErrorCode::NothingToSettle
+        );
+        purchase_record.deposit_settled = true;
+
+        let deposit = purchase_record.deposited;
+        let accepted_sol = presale_info.fair_launch_allocation(deposit)?;
+        let token_amount = quote_tokens_for_sol(
+            accepted_sol,
+            presale_info.token_price,
+            presale_info.token_decimals,
+        )?;
+        let sol_refund = deposit - accepted_sol;
+
+        // Refund the SOL that did not fit under the cap
+        if sol_refund > 0 {
+            **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= sol_refund;
+            **ctx.accounts.depositor.try_borrow_mut_lamports()? += sol_refund;
+            ctx.accounts.sol_vault.refunded += sol_refund;
+        }
+
+        // Transfer the allocation, or credit it for claiming after TGE
+        presale_info.fair_launch_reserved -= token_amount;
+        if presale_info.deferred_delivery {
+            presale_info.tokens_owed += token_amount;
+        } else if token_amount > 0 {
+            let seeds = &[
+                b"presale_info".as_ref(),
+                &[ctx.bumps.presale_info],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.token_vault.to_account_info(),
+                to: ctx.accounts.depositor_token_account.to_account_info(),
+                authority: presale_info.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+            token::transfer(cpi_ctx, token_amount)?;
+            purchase_record.tokens_claimed += token_amount;
+        }
+
+        purchase_record.total_purchased += token_amount;
+        purchase_record.total_sol_spent += accepted_sol;
+        presale_info.tokens_sold += token_amount;
+        presale_info.sol_raised += accepted_sol;
+        presale_info.settled_deposits += deposit;
+
+        // Rounding dust left once every deposit is settled goes back to the authority
+        if presale_info.settled_deposits == presale_info.total_deposits {
+            presale_info.fair_launch_reserved = 0;
+        }
+
+        emit!(DepositSettled {
+            depositor: ctx.accounts.depositor.key(),
+            sol_accepted: accepted_sol,
+            sol_refunded: sol_refund,
+            token_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Append a sale round (only authority, before the sale starts)
+    pub fn add_round(
+        ctx: Context<UpdatePresale>,
+        token_price: u64, // Price in lamports per whole CAT token
+        token_cap: u64,
+        min_purchase: u64,
+        max_purchase: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        require_not_started(presale_info)?;
+        require!(presale_info.rounds.len() < MAX_ROUNDS, ErrorCode::TooManyRounds);
+        require!(!presale_info.dutch_auction, ErrorCode::DutchAuctionNoRounds);
+        require!(token_price > 0, ErrorCode::InvalidTokenPrice);
+        require!(
+            token_cap > 0 && min_purchase <= max_purchase && start_time < end_time,
+            ErrorCode::InvalidRound
+        );
+
+        presale_info.rounds.push(PresaleRound {
+            token_price,
+            token_cap,
+            tokens_sold: 0,
+            sol_raised: 0,
+            min_purchase,
+            max_purchase,
+            start_time,
+            end_time,
+            tge_unlock_bps: BPS_DENOMINATOR,
+            vesting_cliff: 0,
+            vesting_duration: 0,
+            merkle_root: [0; 32],
+        });
+
+        emit!(RoundAdded {
+            round_index: (presale_info.rounds.len() - 1) as u8,
+            token_price,
+            token_cap,
+            min_purchase,
+            max_purchase,
+            start_time,
+            end_time,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the referral bonus, as a share of the referred purchase paid in CAT (only authority, before the sale starts)
+    pub fn set_referral_bps(
+        ctx: Context<UpdatePresale>,
+        referral_bps: u16,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        require_not_started(presale_info)?;
+        require!(referral_bps <= BPS_DENOMINATOR, ErrorCode::InvalidReferralBps);
+        presale_info.referral_bps = referral_bps;
+
+        emit!(ReferralBpsUpdated {
+            referral_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Lock a round's purchases behind a vesting schedule anchored at TGE (only authority, before the sale starts)
+    pub fn set_round_vesting(
+        ctx: Context<UpdatePresale>,
+        round_index: u8,
+        tge_unlock_bps: u16, // Share released at TGE, in basis points
+        vesting_cliff: i64, // Seconds after TGE before linear unlocking starts
+        vesting_duration: i64, // Seconds after the cliff over which the rest unlocks linearly
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        require_not_started(presale_info)?;
+        let round = presale_info
+            .rounds
+            .get_mut(round_index as usize)
+            .ok_or(ErrorCode::InvalidRound)?;
+
+        require!(
+            tge_unlock_bps <= BPS_DENOMINATOR && vesting_cliff >= 0 && vesting_duration >= 0,
+            ErrorCode::InvalidVestingSchedule
+        );
+
+        round.tge_unlock_bps = tge_unlock_bps;
+        round.vesting_cliff = vesting_cliff;
+        round.vesting_duration = vesting_duration;
+
+        emit!(RoundVestingSet {
+            round_index,
+            tge_unlock_bps,
+            vesting_cliff,
+            vesting_duration,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Restrict a round to wallets in a Merkle allowlist of `(wallet, max_allocation)`,
+    /// or open it to the public again with an all-zero root (only authority, before the sale starts)
+    pub fn set_round_whitelist(
+        ctx: Context<UpdatePresale>,
+        round_index: u8,
+        merkle_root: [u8; 32],
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        require_not_started(presale_info)?;
+        let round = presale_info
+            .rounds
+            .get_mut(round_index as usize)
+            .ok_or(ErrorCode::InvalidRound)?;
+
+        round.merkle_root = merkle_root;
+
+        emit!(RoundWhitelistSet {
+            round_index,
+            merkle_root,
+            whitelisted: round.is_whitelisted(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Turn the sale into a Dutch auction decaying from `start_price` at `start_time`
+    /// to `floor_price` at `end_time` (only authority, before the sale starts)
+    pub fn configure_dutch_auction(
+        ctx: Context<UpdatePresale>,
+        start_price: u64, // Lamports per whole CAT at start_time
+        floor_price: u64, // Lamports per whole CAT from end_time on
+        step_interval: i64, // Seconds between price drops, 0 = linear decay
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        require_not_started(presale_info)?;
+        require!(presale_info.rounds.is_empty(), ErrorCode::DutchAuctionNoRounds);
+        require!(!presale_info.fair_launch, ErrorCode::DutchAuctionNoFairLaunch);
+        require!(
+            floor_price > 0 && start_price >= floor_price && step_interval >= 0,
+            ErrorCode::InvalidAuctionConfig
+        );
+
+        presale_info.dutch_auction = true;
+        presale_info.auction_start_price = start_price;
+        presale_info.auction_floor_price = floor_price;
+        presale_info.auction_step_interval = step_interval;
+        presale_info.auction_lowest_price = start_price;
+
+        emit!(DutchAuctionConfigured {
+            start_price,
+            floor_price,
+            step_interval,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Fix the auction clearing price once it has ended: the last price reached if it
+    /// sold out, the floor otherwise. Must run before `finalize_presale`.
+    pub fn settle_auction(
+        ctx: Context<FinalizePresale>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let clock = Clock::get()?;
+
+        require!(presale_info.dutch_auction, ErrorCode::DutchAuctionDisabled);
+        require!(
+            presale_info.auction_clearing_price == 0,
+            ErrorCode::AuctionAlreadySettled
+        );
+        require!(
+            presale_info.status == PresaleStatus::Active
+                || presale_info.status == PresaleStatus::SoldOut,
+            ErrorCode::PresaleAlreadyFinalized
+        );
+        require!(
+            clock.unix_timestamp > presale_info.end_time
+                || presale_info.status == PresaleStatus::SoldOut,
+            ErrorCode::PresaleStillActive
+        );
+
+        let clearing_price = if presale_info.status == PresaleStatus::SoldOut {
+            presale_info.auction_lowest_price
+        } else {
+            presale_info.auction_floor_price
+        };
+
+        // Everything paid above the clearing price is owed back to buyers
+        let sol_at_clearing = quote_sol_for_tokens(
+            presale_info.tokens_sold,
+            clearing_price,
+            presale_info.token_decimals,
+        )?;
+        presale_info.auction_clearing_price = clearing_price;
+        presale_info.auction_refunds_reserved = presale_info.sol_raised.saturating_sub(sol_at_clearing);
+        presale_info.sol_raised = presale_info.sol_raised.min(sol_at_clearing);
+
+        emit!(AuctionSettled {
+            clearing_price,
+            sol_raised: presale_info.sol_raised,
+            refunds_reserved: presale_info.auction_refunds_reserved,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Refund what a buyer paid above the auction clearing price
+    pub fn claim_auction_refund(
+        ctx: Context<ClaimAuctionRefund>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let purchase_record = &mut ctx.accounts.purchase_record;
+
+        require!(presale_info.dutch_auction, ErrorCode::DutchAuctionDisabled);
+        // A failed sale refunds everything through `claim_refund` instead
+        require!(
+            presale_info.status == PresaleStatus::Succeeded,
+            ErrorCode::PresaleNotSucceeded
+        );
+        require!(!purchase_record.auction_refunded, ErrorCode::AlreadyRefunded);
+        purchase_record.auction_refunded = true;
+
+        // Rounded up, so buyers together never take back more than was reserved
+        let cost = quote_sol_for_tokens(
+            purchase_record.total_purchased,
+            presale_info.auction_clearing_price,
+            presale_info.token_decimals,
+        )?;
+        let sol_refund = purchase_record.total_sol_spent.saturating_sub(cost);
+        purchase_record.total_sol_spent -= sol_refund;
+
+        if sol_refund > 0 {
+            **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= sol_refund;
+            **ctx.accounts.buyer.try_borrow_mut_lamports()? += sol_refund;
+            ctx.accounts.sol_vault.refunded += sol_refund;
+        }
+
+        presale_info.auction_refunds_reserved -= sol_refund;
+        presale_info.auction_refunds_settled += 1;
+        // Rounding dust left once every buyer is refunded goes back to the authority
+        if presale_info.auction_refunds_settled == presale_info.total_buyers {
+            presale_info.auction_refunds_reserved = 0;
+        }
+
+        emit!(AuctionRefundClaimed {
+            buyer: ctx.accounts.buyer.key(),
+            clearing_price: presale_info.auction_clearing_price,
+            sol_refunded: sol_refund,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Add tokens to presale vault (only authority)
+    pub fn fund_presale(
+        ctx: Context<FundPresale>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority_token_account.to_account_info(),
+            to: ctx.accounts.token_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(PresaleFunded {
+            funder: ctx.accounts.authority.key(),
+            amount,
+            vault_balance: ctx.accounts.token_vault.amount + amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Queue a settings change that can be executed after `timelock_delay` (only authority).
+    /// The queued change is visible on `PresaleInfo` until it is executed or cancelled.
+    pub fn queue_presale_update(
+        ctx: Context<UpdatePresale>,
+        update: PresaleUpdate,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let clock = Clock::get()?;
+
+        require!(presale_info.pending_update.is_none(), ErrorCode::UpdateAlreadyQueued);
+        if let Some(token_price) = update.new_token_price {
+            require!(token_price > 0, ErrorCode::InvalidTokenPrice);
+        }
+        if let Some(timelock_delay) = update.new_timelock_delay {
+            require!(
+                (0..=MAX_TIMELOCK_DELAY).contains(&timelock_delay),
+                ErrorCode::InvalidTimelockDelay
+            );
+        }
+
+        let eta = clock
+            .unix_timestamp
+            .checked_add(presale_info.timelock_delay)
+            .ok_or(ErrorCode::MathOverflow)?;
+        presale_info.pending_update = Some(update.clone());
+        presale_info.pending_update_eta = eta;
+
+        emit!(PresaleUpdateQueued {
+            update,
+            eta,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Apply the queued settings change once its delay has passed (only authority)
+    pub fn execute_presale_update(
+        ctx: Context<UpdatePresale>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let clock = Clock::get()?;
+
+        let update = presale_info
+            .pending_update
+            .take()
+            .ok_or(ErrorCode::NoUpdateQueued)?;
+        require!(
+            clock.unix_timestamp >= presale_info.pending_update_eta,
+            ErrorCode::TimelockNotExpired
+        );
+        presale_info.pending_update_eta = 0;
+
+        if let Some(token_price) = update.new_token_price {
+            require!(token_price > 0, ErrorCode::InvalidTokenPrice);
+            // Fair launch allocations are sized at this price when the sale is finalized
+            require!(
+                presale_info.status == PresaleStatus::Active,
+                ErrorCode::PresaleAlreadyFinalized
+            );
+            presale_info.token_price = token_price;
+        }
+
+        if let Some(end_time) = update.new_end_time {
+            // An extended sale must still end before the scheduled TGE
+            require!(
+                presale_info.tge_time == 0 || end_time <= presale_info.tge_time,
+                ErrorCode::InvalidTgeSchedule
+            );
+            presale_info.end_time = end_time;
+        }
+
+        if let Some(is_active) = update.new_is_active {
+            presale_info.is_active = is_active;
+            presale_info.closed = !is_active;
+        }
+
+        if let Some(min_purchase) = update.new_min_purchase {
+            presale_info.min_purchase = min_purchase;
+        }
+
+        if let Some(max_purchase) = update.new_max_purchase {
+            presale_info.max_purchase = max_purchase;
+        }
+
+        if let Some(split_across_rounds) = update.new_split_across_rounds {
+            presale_info.split_across_rounds = split_across_rounds;
+        }
+
+        if let Some(deferred_delivery) = update.new_deferred_delivery {
+            // Switching modes mid-sale would mix delivered and credited purchases
+            require!(presale_info.tokens_sold == 0, ErrorCode::SaleAlreadyStarted);
+            // Credited purchases need a TGE, and `set_tge` closes at `start_time`
+            if deferred_delivery {
+                require_not_started(presale_info)?;
+            }
+            presale_info.deferred_delivery = deferred_delivery;
+        }
+
+        if let Some(fair_launch) = update.new_fair_launch {
+            // Switching between buying and depositing changes what buyers get for their SOL
+            require_not_started(presale_info)?;
+            require!(!presale_info.dutch_auction, ErrorCode::DutchAuctionNoFairLaunch);
+            presale_info.fair_launch = fair_launch;
+        }
+
+        if let Some(timelock_delay) = update.new_timelock_delay {
+            presale_info.timelock_delay = timelock_delay;
+        }
+
+        emit!(PresaleUpdated {
+            update,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Drop the queued settings change (only authority)
+    pub fn cancel_presale_update(
+        ctx: Context<UpdatePresale>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        require!(presale_info.pending_update.is_some(), ErrorCode::NoUpdateQueued);
+        presale_info.pending_update = None;
+        presale_info.pending_update_eta = 0;
+
+        emit!(PresaleUpdateCancelled {
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Stop purchases immediately, bypassing the timelock (only authority).
+    /// Resuming goes through `queue_presale_update`.
+    pub fn pause_presale(
+        ctx: Context<UpdatePresale>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        presale_info.is_active = false;
+
+        emit!(PresalePaused {
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Propose a new authority for the presale; it takes over once it calls `accept_authority` (only authority)
+    pub fn propose_authority(
+        ctx: Context<UpdatePresale>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        require!(
+            new_authority != Pubkey::default() && new_authority != presale_info.authority,
+            ErrorCode::InvalidNewAuthority
+        );
+        presale_info.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            config: presale_info.key(),
+            authority: presale_info.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Complete a proposed authority transfer (only the pending authority)
+    pub fn accept_authority(
+        ctx: Context<AcceptPresaleAuthority>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        let previous_authority = presale_info.authority;
+        presale_info.authority = ctx.accounts.new_authority.key();
+        presale_info.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferred {
+            config: presale_info.key(),
+            previous_authority,
+            new_authority: presale_info.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw a proposed authority transfer (only authority)
+    pub fn cancel_authority_transfer(
+        ctx: Context<UpdatePresale>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        require!(
+            presale_info.pending_authority != Pubkey::default(),
+            ErrorCode::NoPendingAuthority
+        );
+        emit!(AuthorityTransferCancelled {
+            config: presale_info.key(),
+            authority: presale_info.authority,
+            cancelled_authority: presale_info.pending_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        presale_info.pending_authority = Pubkey::default();
+
+        Ok(())
+    }
+
+    /// Accept an SPL token as payment at a fixed price (only authority, before the sale starts)
+    pub fn add_payment_mint(
+        ctx: Context<AddPaymentMint>,
+        token_price: u64, // Price in payment token base units per whole CAT token
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let mint = ctx.accounts.payment_mint.key();
+
+        require_not_started(presale_info)?;
+        require!(
+            presale_info.payment_mints.len() < MAX_PAYMENT_MINTS,
+            ErrorCode::TooManyPaymentMints
+        );
+        require!(
+            !presale_info.payment_mints.iter().any(|payment_mint| payment_mint.mint == mint),
+            ErrorCode::PaymentMintAlreadyAccepted
+        );
+        require!(token_price > 0, ErrorCode::InvalidTokenPrice);
+
+        presale_info.payment_mints.push(PaymentMint {
+            mint,
+            vault: ctx.accounts.payment_vault.key(),
+            token_price,
+            raised: 0,
+            withdrawn: 0,
+        });
+
+        emit!(PaymentMintAdded {
+            mint,
+            vault: ctx.accounts.payment_vault.key(),
+            token_price,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the wallets that `distribute_proceeds` splits raised SOL between (only authority, before the sale starts).
+    /// Shares must add up to 100%; wallets kept from the previous list keep their payout history.
+    pub fn set_payees(
+        ctx: Context<UpdatePresale>,
+        payees: Vec<PayeeShare>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        require_not_started(presale_info)?;
+        require!(
+            !payees.is_empty() && payees.len() <= MAX_PAYEES,
+            ErrorCode::InvalidPayees
+        );
+        let total_bps: u32 = payees.iter().map(|payee| payee.share_bps as u32).sum();
+        require!(total_bps == BPS_DENOMINATOR as u32, ErrorCode::InvalidPayees);
+        for (index, payee) in payees.iter().enumerate() {
+            require!(
+                !payees[..index].iter().any(|other| other.wallet == payee.wallet),
+                ErrorCode::InvalidPayees
+            );
+        }
+
+        presale_info.payees = payees
+            .iter()
+            .map(|payee| Payee {
+                wallet: payee.wallet,
+                share_bps: payee.share_bps,
+                total_paid: presale_info
+                    .payees
+                    .iter()
+                    .find(|existing| existing.wallet == payee.wallet)
+                    .map_or(0, |existing| existing.total_paid),
+            })
+            .collect();
+
+        emit!(PayeesUpdated {
+            payees,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Split the withdrawable `sol_vault` balance between the payees (anyone can call).
+    ///
+    /// `remaining_accounts` must carry every payee wallet, writable, in `PresaleInfo::payees` order.
+    pub fn distribute_proceeds(
+        ctx: Context<DistributeProceeds>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        // Raised funds stay locked until the soft cap is confirmed
+        require!(
+            presale_info.status == PresaleStatus::Succeeded,
+            ErrorCode::PresaleNotSucceeded
+        );
+        require!(!presale_info.payees.is_empty(), ErrorCode::InvalidPayees);
+        require!(
+            ctx.remaining_accounts.len() == presale_info.payees.len(),
+            ErrorCode::MissingPayeeAccounts
+        );
+
+        // Keep the vault rent exempt and leave what is still owed back to buyers
+        let distributable = sol_vault_balance(presale_info, &ctx.accounts.sol_vault)?.available;
+        require!(distributable > 0, ErrorCode::NothingToDistribute);
+
+        for (payee, payee_account) in presale_info.payees.iter_mut().zip(ctx.remaining_accounts.iter()) {
+            require_keys_eq!(payee_account.key(), payee.wallet, ErrorCode::MissingPayeeAccounts);
+
+            // Rounded down, the dust stays in the vault for the next distribution
+            let amount = (distributable as u128 * payee.share_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+            if amount == 0 {
+                continue;
+            }
+
+            **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+            **payee_account.try_borrow_mut_lamports()? += amount;
+            ctx.accounts.sol_vault.withdrawn += amount;
+            payee.total_paid += amount;
+
+            emit!(PayeePaid {
+                wallet: payee.wallet,
+                amount,
+                total_paid: payee.total_paid,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+
+        emit!(ProceedsDistributed {
+            amount: distributable,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Schedule the token generation event that unlocks credited purchases, at most
+    /// `MAX_TGE_DELAY` after the sale ends (only authority, before the sale starts)
+    pub fn set_tge(
+        ctx: Context<UpdatePresale>,
+        tge_time: i64,
+        tge_unlock_bps: u16, // Share released at TGE, in basis points
+        vesting_duration: i64, // Seconds after TGE over which the rest unlocks linearly
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let clock = Clock::get()?;
+
+        // Buyers purchase against this schedule, so it is fixed once the sale starts
+        require_not_started(presale_info)?;
+        require!(
+            presale_info.deferred_delivery
+                || presale_info.rounds.iter().any(|round| round.is_vested()),
+            ErrorCode::TgeNotUsed
+        );
+        require!(
+            tge_time >= presale_info.end_time
+                && tge_time <= presale_info.end_time + MAX_TGE_DELAY
+                && tge_unlock_bps <= BPS_DENOMINATOR
+                && (0..=MAX_VESTING_DURATION).contains(&vesting_duration),
+            ErrorCode::InvalidTgeSchedule
+        );
+
+        presale_info.tge_time = tge_time;
+        presale_info.tge_unlock_bps = tge_unlock_bps;
+        presale_info.vesting_duration = vesting_duration;
+
+        emit!(TgeScheduled {
+            tge_time,
+            tge_unlock_bps,
+            vesting_duration,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Claim credited tokens that have unlocked since TGE
+    pub fn claim_purchased_tokens(
+        ctx: Context<ClaimPurchasedTokens>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let purchase_record = &mut ctx.accounts.purchase_record;
+        let clock = Clock::get()?;
+
+        require!(presale_info.deferred_delivery, ErrorCode::DeferredDeliveryDisabled);
+        require!(
+            presale_info.tge_time > 0 && clock.unix_timestamp >= presale_info.tge_time,
+            ErrorCode::TgeNotReached
+        );
+        require!(
+            presale_info.status == PresaleStatus::Succeeded,
+            ErrorCode::PresaleNotSucceeded
+        );
+
+        // Vested round purchases follow their own schedule through `release_vested`
+        let vested_purchased = purchase_record.vested_purchased(&presale_info.rounds);
+        let liquid_claimed = purchase_record.tokens_claimed - purchase_record.vested_released();
+        let claimable = presale_info
+            .unlocked_amount(purchase_record.total_purchased - vested_purchased, clock.unix_timestamp)
+            - liquid_claimed;
+        require!(claimable > 0, ErrorCode::NothingToClaim);
+
+        purchase_record.tokens_claimed += claimable;
+        presale_info.tokens_owed -= claimable;
+
+        let seeds = &[
+            b"presale_info".as_ref(),
+            &[ctx.bumps.presale_info],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.token_vault.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: presale_info.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, claimable)?;
+
+        emit!(PurchasedTokensClaimed {
+            buyer: ctx.accounts.buyer.key(),
+            amount: claimable,
+            tokens_claimed: purchase_record.tokens_claimed,
+            total_purchased: purchase_record.total_purchased,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Release round purchases that have vested since TGE
+    pub fn release_vested(
+        ctx: Context<ClaimPurchasedTokens>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let purchase_record = &mut ctx.accounts.purchase_record;
+        let clock = Clock::get()?;
+
+        require!(
+            presale_info.tge_time > 0 && clock.unix_timestamp >= presale_info.tge_time,
+            ErrorCode::TgeNotReached
+        );
+        require!(
+            presale_info.status == PresaleStatus::Succeeded,
+            ErrorCode::PresaleNotSucceeded
+        );
+
+        let mut releasable = 0u64;
+        for (index, round) in presale_info.rounds.iter().enumerate() {
+            if !round.is_vested() {
+                continue;
+            }
+            let unlocked = round.unlocked_amount(
+                purchase_record.round_purchased[index],
+                presale_info.tge_time,
+                clock.unix_timestamp,
+            );
+            let amount = unlocked - purchase_record.round_released[index];
+            purchase_record.round_released[index] += amount;
+            releasable += amount;
+        }
+        require!(releasable > 0, ErrorCode::NothingToClaim);
+
+        purchase_record.tokens_claimed += releasable;
+        presale_info.tokens_owed -= releasable;
+
+        let seeds = &[
+            b"presale_info".as_ref(),
+            &[ctx.bumps.presale_info],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.token_vault.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: presale_info.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, releasable)?;
+
+        emit!(VestedTokensReleased {
+            buyer: ctx.accounts.buyer.key(),
+            amount: releasable,
+            vested_released: purchase_record.vested_released(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Settle the sale as succeeded or failed against the soft cap, once it has ended
+    pub fn finalize_presale(
+        ctx: Context<FinalizePresale>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let clock = Clock::get()?;
+
+        // A sold out sale can be settled right away
+        require!(
+            presale_info.status == PresaleStatus::Active
+                || presale_info.status == PresaleStatus::SoldOut,
+            ErrorCode::PresaleAlreadyFinalized
+        );
+        require!(
+            clock.unix_timestamp > presale_info.end_time
+                || presale_info.status == PresaleStatus::SoldOut,
+            ErrorCode::PresaleStillActive
+        );
+
+        require!(
+            !presale_info.dutch_auction || presale_info.auction_clearing_price > 0,
+            ErrorCode::AuctionNotSettled
+        );
+
+        // SPL purchases count at their SOL value, fair launch deposits up to what the unsold tokens are worth
+        let mut sol_raised = presale_info.sol_raised + presale_info.payment_sol_value;
+        if presale_info.fair_launch && presale_info.total_deposits > 0 {
+            sol_raised += presale_info.size_fair_launch()?;
+        }
+
+        presale_info.status = if sol_raised >= presale_info.soft_cap {
+            PresaleStatus::Succeeded
+        } else {
+            PresaleStatus::Failed
+        };
+        // Deposits of a failed sale are refunded in full, nothing is settled against the reserve
+        if presale_info.status == PresaleStatus::Failed {
+            presale_info.fair_launch_reserved = 0;
+        }
+
+        emit!(PresaleFinalized {
+            status: presale_info.status,
+            sol_raised,
+            soft_cap: presale_info.soft_cap,
+            fair_launch_deposits: presale_info.total_deposits,
+            fair_launch_sol_cap: presale_info.fair_launch_sol_cap,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Return purchased CAT and get the SOL and SPL payments back after a failed sale.
+    ///
+    /// For every payment mint the buyer spent, `remaining_accounts` must carry the
+    /// mint's payment vault followed by the buyer's token account for that mint,
+    /// in `PresaleInfo::payment_mints` order.
+    pub fn claim_refund<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimRefund<'info>>,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+        let purchase_record = &mut ctx.accounts.purchase_record;
+
+        require!(
+            presale_info.status == PresaleStatus::Failed,
+            ErrorCode::PresaleNotFailed
+        );
+        require!(!purchase_record.refunded, ErrorCode::AlreadyRefunded);
+        purchase_record.refunded = true;
+
+        // Credited tokens that were never delivered are simply cancelled
+        presale_info.tokens_owed -= purchase_record.total_purchased - purchase_record.tokens_claimed;
+
+        // Buyer hands the delivered CAT back to the vault
+        if purchase_record.tokens_claimed > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.buyer_token_account.to_account_info(),
+                to: ctx.accounts.token_vault.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+            token::transfer(cpi_ctx, purchase_record.tokens_claimed)?;
+        }
+
+        // Refund SOL from the vault, including any fair launch deposit
+        let sol_refund = purchase_record.total_sol_spent + purchase_record.deposited;
+        if sol_refund > 0 {
+            **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= sol_refund;
+            **ctx.accounts.buyer.try_borrow_mut_lamports()? += sol_refund;
+            ctx.accounts.sol_vault.refunded += sol_refund;
+        }
+
+        // Refund SPL payments from their vaults
+        let seeds = &[
+            b"presale_info".as_ref(),
+            &[ctx.bumps.presale_info],
+        ];
+        let signer = &[&seeds[..]];
+
+        let mut remaining_accounts = ctx.remaining_accounts.iter();
+        for (payment_index, payment_mint) in presale_info.payment_mints.iter().enumerate() {
+            let amount = purchase_record.token_spent[payment_index];
+            if amount == 0 {
+                continue;
+            }
+
+            let payment_vault = remaining_accounts
+                .next()
+                .ok_or(ErrorCode::MissingRefundAccounts)?;
+            let buyer_payment_account: Account<'info, TokenAccount> = Account::try_from(
+                remaining_accounts
+                    .next()
+                    .ok_or(ErrorCode::MissingRefundAccounts)?,
+            )?;
+            require_keys_eq!(payment_vault.key(), payment_mint.vault, ErrorCode::MissingRefundAccounts);
+            require_keys_eq!(buyer_payment_account.mint, payment_mint.mint, ErrorCode::MissingRefundAccounts);
+            require_keys_eq!(
+                buyer_payment_account.owner,
+                ctx.accounts.buyer.key(),
+                ErrorCode::MissingRefundAccounts
+            );
+
+            let cpi_accounts = Transfer {
+                from: payment_vault.to_account_info(),
+                to: buyer_payment_account.to_account_info(),
+                authority: presale_info.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+            token::transfer(cpi_ctx, amount)?;
+
+            emit!(PaymentTokensRefunded {
+                buyer: ctx.accounts.buyer.key(),
+                payment_mint: payment_mint.mint,
+                amount,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+
+        emit!(RefundClaimed {
+            buyer: ctx.accounts.buyer.key(),
+            tokens_returned: purchase_record.tokens_claimed,
+            sol_refunded: sol_refund,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw SOL raised (only authority, after the sale succeeded, when no payees are set)
+    pub fn withdraw_sol(
+        ctx: Context<WithdrawSOL>,
+        amount: u64,
+    ) -> Result<()> {
+        let presale_info = &ctx.accounts.presale_info;
+
+        // Raised funds stay locked until the soft cap is confirmed
+        require!(
+            presale_info.status == PresaleStatus::Succeeded,
+            ErrorCode::PresaleNotSucceeded
+        );
+        // Once payees are set the split is binding, proceeds only leave through `distribute_proceeds`
+        require!(presale_info.payees.is_empty(), ErrorCode::ProceedsGoToPayees);
+
+        // Check if withdrawal amount is available above rent and outstanding refunds
+        let balance = sol_vault_balance(presale_info, &ctx.accounts.sol_vault)?;
+        require!(amount <= balance.available, ErrorCode::InsufficientSOLInVault);
+
+        // Transfer SOL from vault to authority
+        **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.try_borrow_mut_lamports()? += amount;
+        ctx.accounts.sol_vault.withdrawn += amount;
+
+        emit!(SolWithdrawn {
+            authority: ctx.accounts.authority.key(),
+            amount,
+            total_withdrawn: ctx.accounts.sol_vault.withdrawn,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw SPL payment tokens raised (only authority, after the sale succeeded)
+    pub fn withdraw_payment_tokens(
+        ctx: Context<WithdrawPaymentTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        let presale_info = &mut ctx.accounts.presale_info;
+
+        // Raised funds stay locked until the soft cap is confirmed
+        require!(
+            presale_info.status == PresaleStatus::Succeeded,
+            ErrorCode::PresaleNotSucceeded
+        );
+
+        let payment_index = presale_info
+            .payment_mints
+            .iter()
+            .position(|payment_mint| payment_mint.mint == ctx.accounts.payment_mint.key())
+            .ok_or(ErrorCode::PaymentMintNotAccepted)?;
+
+        // Check if withdrawal amount is available
+        require!(
+            ctx.accounts.payment_vault.amount >= amount,
+            ErrorCode::InsufficientPaymentTokensInVault
+        );
+
+        let seeds = &[
+            b"presale_info".as_ref(),
+            &[ctx.bumps.presale_info],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payment_vault.to_account_info(),
+            to: ctx.accounts.authority_payment_account.to_account_info(),
+            authority: presale_info.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        presale_info.payment_mints[payment_index].withdrawn += amount;
+
+        emit!(PaymentTokensWithdrawn {
+            authority: ctx.accounts.authority.key(),
+            payment_mint: ctx.accounts.payment_mint.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw remaining tokens (only authority, after presale ends)
+    pub fn withdraw_remaining_tokens(
+        ctx: Context<WithdrawRemainingTokens>,
+    ) -> Result<()> {
+        let presale_info = &ctx.accounts.presale_info;
+        let clock = Clock::get()?;
+
+        // Check if presale has ended, or was closed through the timelock. An emergency pause
+        // alone does not release the vault.
+        require!(
+            clock.unix_timestamp > presale_info.end_time
+                || presale_info.closed
+                || presale_info.status != PresaleStatus::Active,
+            ErrorCode::PresaleStillActive
+        );
+        // Fair launch deposits are only sized into `fair_launch_reserved` at finalization,
+        // until then every token in the vault may be owed to depositors
+        require!(
+            !presale_info.fair_launch
+                || matches!(presale_info.status, PresaleStatus::Succeeded | PresaleStatus::Failed),
+            ErrorCode::PresaleNotFinalized
+        );
+
+        // Tokens credited to buyers but not yet claimed, reserved for unsettled deposits or
+        // earned as referral rewards stay in the vault. After a failed sale none of them is
+        // ever delivered, refunds only cancel the credits and hand delivered CAT back.
+        let obligations = if presale_info.status == PresaleStatus::Failed {
+            0
+        } else {
+            presale_info.tokens_owed
+                + presale_info.fair_launch_reserved
+                + presale_info.referral_rewards_owed
+        };
+        require!(
+            ctx.accounts.token_vault.amount >= obligations,
+            ErrorCode::VaultBelowObligations
+        );
+        let remaining_balance = ctx.accounts.token_vault.amount - obligations;
+
+        if remaining_balance > 0 {
+            let seeds = &[
+                b"presale_info".as_ref(),
+                &[ctx.bumps.presale_info],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.token_vault.to_account_info(),
+                to: ctx.accounts.authority_token_account.to_account_info(),
+                authority: ctx.accounts.presale_info.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+            token::transfer(cpi_ctx, remaining_balance)?;
+
+            emit!(RemainingTokensWithdrawn {
+                authority: ctx.accounts.authority.key(),
+                amount: remaining_balance,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// CAT base units bought with `sol_amount` lamports at `token_price` lamports per
+/// whole token. Rounds down so the buyer never receives more than they paid for.
+pub fn quote_tokens_for_sol(sol_amount: u64, token_price: u64, decimals: u8) -> Result<u64> {
+    require!(token_price > 0, ErrorCode::InvalidTokenPrice);
+    let token_amount = (sol_amount as u128)
+        .checked_mul(token_unit(decimals)?)
+        .ok_or(ErrorCode::MathOverflow)?
+        / token_price as u128;
+    Ok(u64::try_from(token_amount).map_err(|_| ErrorCode::MathOverflow)?)
+}
+
+/// Lamports needed to buy `token_amount` base units at `token_price` lamports per
+/// whole token. Rounds up so the protocol is never underpaid.
+pub fn quote_sol_for_tokens(token_amount: u64, token_price: u64, decimals: u8) -> Result<u64> {
+    let unit = token_unit(decimals)?;
+    let cost = (token_amount as u128)
+        .checked_mul(token_price as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    // Manual ceiling division, `u128::div_ceil` is newer than the SBF toolchain
+    #[allow(clippy::manual_div_ceil)]
+    let sol_amount = (cost + unit - 1) / unit;
+    Ok(u64::try_from(sol_amount).map_err(|_| ErrorCode::MathOverflow)?)
+}
+
+/// Break down the `sol_vault` balance. What is withdrawable excludes the rent exempt
+/// minimum and everything still owed back to buyers.
+pub fn sol_vault_balance(presale_info: &PresaleInfo, sol_vault: &Account<SolVault>) -> Result<SolVaultBalance> {
+    let balance = sol_vault.get_lamports();
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(8 + SolVault::INIT_SPACE);
+    let outstanding_refunds = if presale_info.status == PresaleStatus::Failed {
+        sol_vault.raised - sol_vault.refunded
+    } else {
+        (presale_info.total_deposits - presale_info.settled_deposits) + presale_info.auction_refunds_reserved
+    };
+
+    Ok(SolVaultBalance {
+        balance,
+        rent_exempt_minimum,
+        raised: sol_vault.raised,
+        withdrawn: sol_vault.withdrawn,
+        refunded: sol_vault.refunded,
+        outstanding_refunds,
+        available: balance.saturating_sub(rent_exempt_minimum + outstanding_refunds),
+    })
+}
+
+/// Settings buyers rely on can only change instantly while the sale has not started
+fn require_not_started(presale_info: &PresaleInfo) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp < presale_info.start_time,
+        ErrorCode::SaleAlreadyStarted
+    );
+    Ok(())
+}
+
+/// Base units in one whole token
+fn token_unit(decimals: u8) -> Result<u128> {
+    Ok(10u128
+        .checked_pow(decimals as u32)
+        .ok_or(ErrorCode::MathOverflow)?)
+}
+
+#[derive(Accounts)]
+pub struct InitializePresale<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PresaleInfo::INIT_SPACE,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = token_mint,
+        token::authority = presale_info,
+        seeds = [b"token_vault"],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SolVault::INIT_SPACE,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct BuyTokens<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + PurchaseRecord::INIT_SPACE,
+        seeds = [b"purchase_record", buyer.key().as_ref()],
+        bump
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault"],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+
+    #[account(
+        mut,
+        token::mint = presale_info.token_mint,
+        token::authority = buyer,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"referral", referral_record.referrer.as_ref()],
+        bump
+    )]
+    pub referral_record: Option<Account<'info, ReferralRecord>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct BuyTokensWithToken<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + PurchaseRecord::INIT_SPACE,
+        seeds = [b"purchase_record", buyer.key().as_ref()],
+        bump
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault"],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub payment_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"payment_vault", payment_mint.key().as_ref()],
+        bump
+    )]
+    pub payment_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer,
+    )]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = presale_info.token_mint,
+        token::authority = buyer,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(
+        seeds = [b"purchase_record", referrer.key().as_ref()],
+        bump,
+        constraint = purchase_record.buyer == referrer.key() @ ErrorCode::ReferrerHasNoPurchase
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + ReferralRecord::INIT_SPACE,
+        seeds = [b"referral", referrer.key().as_ref()],
+        bump
+    )]
+    pub referral_record: Account<'info, ReferralRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    pub referrer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"referral", referrer.key().as_ref()],
+        bump,
+        has_one = referrer
+    )]
+    pub referral_record: Account<'info, ReferralRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault"],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = presale_info.token_mint,
+        token::authority = referrer,
+    )]
+    pub referrer_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetSolVaultBalance<'info> {
+    #[account(
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+}
+
+#[derive(Accounts)]
+pub struct DepositSol<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + PurchaseRecord::INIT_SPACE,
+        seeds = [b"purchase_record", buyer.key().as_ref()],
+        bump
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleDeposit<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub depositor: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_record", depositor.key().as_ref()],
+        bump
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault"],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+
+    #[account(
+        mut,
+        token::mint = presale_info.token_mint,
+        token::authority = depositor,
+    )]
+    pub depositor_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct QuotePurchase<'info> {
+    #[account(
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+}
+
+#[derive(Accounts)]
+pub struct FundPresale<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump,
+        has_one = authority
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(mut)]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault"],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePresale<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump,
+        has_one = authority
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptPresaleAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump,
+        constraint = presale_info.pending_authority == new_authority.key() @ ErrorCode::NotPendingAuthority
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+}
+
+#[derive(Accounts)]
+pub struct AddPaymentMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump,
+        has_one = authority
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    pub payment_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = payment_mint,
+        token::authority = presale_info,
+        seeds = [b"payment_vault", payment_mint.key().as_ref()],
+        bump
+    )]
+    pub payment_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPurchasedTokens<'info> {
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_record", buyer.key().as_ref()],
+        bump,
+        has_one = buyer
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault"],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = presale_info.token_mint,
+        token::authority = buyer,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizePresale<'info> {
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAuctionRefund<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_record", buyer.key().as_ref()],
+        bump,
+        has_one = buyer
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_record", buyer.key().as_ref()],
+        bump,
+        has_one = buyer
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault"],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+
+    #[account(
+        mut,
+        token::mint = presale_info.token_mint,
+        token::authority = buyer,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSOL<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump,
+        has_one = authority
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeProceeds<'info> {
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Account<'info, SolVault>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPaymentTokens<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump,
+        has_one = authority
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    pub payment_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"payment_vault", payment_mint.key().as_ref()],
+        bump
+    )]
+    pub payment_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+    )]
+    pub authority_payment_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawRemainingTokens<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"presale_info"],
+        bump,
+        has_one = authority
+    )]
+    pub presale_info: Account<'info, PresaleInfo>,
+
+    #[account(mut)]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault"],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace, Default)]
+pub struct PresaleInfo {
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub sol_vault: Pubkey,
+    pub token_decimals: u8,
+    pub token_price: u64, // Price in lamports per whole token
+    pub total_tokens_for_sale: u64,
+    pub tokens_sold: u64,
+    pub sol_raised: u64,
+    pub payment_sol_value: u64, // SPL purchases valued in lamports at `token_price`
+    pub total_buyers: u64,
+    pub min_purchase: u64,
+    pub max_purchase: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub is_active: bool,
+    pub closed: bool, // Deactivated through the timelock, unlike `pause_presale`
+    pub split_across_rounds: bool, // Spill a purchase into the next round instead of rejecting it
+    #[max_len(MAX_ROUNDS)]
+    pub rounds: Vec<PresaleRound>,
+    #[max_len(MAX_PAYMENT_MINTS)]
+    pub payment_mints: Vec<PaymentMint>,
+    #[max_len(MAX_PAYEES)]
+    pub payees: Vec<Payee>,
+    pub soft_cap: u64, // Lamports
+    pub hard_cap_lamports: u64,
+    pub status: PresaleStatus,
+    pub deferred_delivery: bool, // Purchases are credited and claimed after TGE
+    pub tokens_owed: u64, // Credited to buyers but not yet delivered
+    pub tge_time: i64, // 0 = not scheduled
+    pub tge_unlock_bps: u16,
+    pub vesting_duration: i64,
+    pub fair_launch: bool, // Buyers deposit SOL and receive a pro-rata allocation after the sale
+    pub total_deposits: u64,
+    pub settled_deposits: u64,
+    pub fair_launch_sol_cap: u64, // Lamports the unsold tokens were worth at finalization
+    pub fair_launch_reserved: u64, // Tokens held back for deposits that are not settled yet
+    pub dutch_auction: bool, // Price decays from auction_start_price to auction_floor_price
+    pub auction_start_price: u64,
+    pub auction_floor_price: u64,
+    pub auction_step_interval: i64, // 0 = linear decay
+    pub auction_lowest_price: u64, // Lowest price any purchase was made at
+    pub auction_clearing_price: u64, // 0 until `settle_auction`
+    pub auction_refunds_reserved: u64, // Lamports still owed back to buyers
+    pub auction_refunds_settled: u64, // Buyers that claimed their auction refund
+    pub referral_bps: u16, // Referrer bonus as a share of the referred purchase
+    pub referral_rewards_owed: u64, // Referral bonus CAT earned but not yet claimed
+    pub pending_authority: Pubkey, // Default = no transfer pending
+    pub timelock_delay: i64, // Seconds between queueing and executing a settings change
+    pub pending_update: Option<PresaleUpdate>,
+    pub pending_update_eta: i64,
+}
+
+/// Settings change queued behind the timelock, `None` fields are left unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PresaleUpdate {
+    pub new_token_price: Option<u64>,
+    pub new_end_time: Option<i64>,
+    pub new_is_active: Option<bool>,
+    pub new_min_purchase: Option<u64>,
+    pub new_max_purchase: Option<u64>,
+    pub new_split_across_rounds: Option<bool>,
+    pub new_deferred_delivery: Option<bool>,
+    pub new_fair_launch: Option<bool>,
+    pub new_timelock_delay: Option<i64>,
+}
+
+/// Program-owned account holding raised SOL, with a ledger of where it went
+#[account]
+#[derive(InitSpace)]
+pub struct SolVault {
+    pub raised: u64, // Lamports paid in by buyers, including fair launch deposits
+    pub withdrawn: u64, // Lamports paid out to the authority and payees
+    pub refunded: u64, // Lamports paid back to buyers
+}
+
+/// Returned by `quote_purchase`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PurchaseQuote {
+    pub token_amount: u64, // CAT base units filled
+    pub sol_amount: u64, // Lamports taken after the hard cap clamp
+}
+
+/// Returned by `get_sol_vault_balance`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SolVaultBalance {
+    pub balance: u64,
+    pub rent_exempt_minimum: u64,
+    pub raised: u64,
+    pub withdrawn: u64,
+    pub refunded: u64,
+    pub outstanding_refunds: u64, // Still owed back to buyers
+    pub available: u64, // Withdrawable now
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Default)]
+pub enum PresaleStatus {
+    #[default]
+    Active,
+    SoldOut, // Hard cap or token allocation reached, no more purchases
+    Succeeded, // Soft cap reached, raised funds can be withdrawn
+    Failed, // Soft cap missed, buyers can claim refunds
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct Payee {
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+    pub total_paid: u64, // Lamports paid out by `distribute_proceeds`
+}
+
+/// Payee entry passed to `set_payees`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PayeeShare {
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PaymentMint {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub token_price: u64, // Price in payment token base units per whole CAT token
+    pub raised: u64,
+    pub withdrawn: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PresaleRound {
+    pub token_price: u64, // Price in lamports per whole token
+    pub token_cap: u64,
+    pub tokens_sold: u64,
+    pub sol_raised: u64,
+    pub min_purchase: u64,
+    pub max_purchase: u64, // Per wallet within this round
+    pub start_time: i64,
+    pub end_time: i64,
+    pub tge_unlock_bps: u16, // BPS_DENOMINATOR = fully liquid at purchase
+    pub vesting_cliff: i64, // Seconds after TGE
+    pub vesting_duration: i64, // Seconds after the cliff
+    pub merkle_root: [u8; 32], // Allowlist of (wallet, max_allocation), all zero = public
+}
+
+impl PresaleRound {
+    pub fn is_open(&self, now: i64) -> bool {
+        now >= self.start_time && now <= self.end_time && self.tokens_sold < self.token_cap
+    }
+
+    pub fn is_whitelisted(&self) -> bool {
+        self.merkle_root != [0; 32]
+    }
+
+    pub fn is_vested(&self) -> bool {
+        self.tge_unlock_bps < BPS_DENOMINATOR
+    }
+
+    /// Part of `total` unlocked at `now`: the TGE share, then the rest linearly after the cliff
+    pub fn unlocked_amount(&self, total: u64, tge_time: i64, now: i64) -> u64 {
+        if now < tge_time {
+            return 0;
+        }
+        let at_tge = (total as u128 * self.tge_unlock_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let vesting_start = tge_time + self.vesting_cliff;
+        if now < vesting_start {
+            return at_tge;
+        }
+        let elapsed = now - vesting_start;
+        if elapsed >= self.vesting_duration {
+            return total;
+        }
+        let vested = (total - at_tge) as u128 * elapsed as u128 / self.vesting_duration as u128;
+        at_tge + vested as u64
+    }
+}
+
+/// Proof that a wallet is allowed `max_allocation` CAT base units in a whitelisted round
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WhitelistProof {
+    pub index: u64,
+    pub max_allocation: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// Part of a purchase priced in a single round, `None` meaning the flat `token_price`
+pub struct PurchaseFill {
+    pub round: Option<usize>,
+    pub token_price: u64,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+}
+
+impl PresaleInfo {
+    /// Lamports of `deposit` accepted by the fair launch: min(deposit, deposit * cap / total_deposits)
+    pub fn fair_launch_allocation(&self, deposit: u64) -> Result<u64> {
+        if self.total_deposits <= self.fair_launch_sol_cap {
+            return Ok(deposit);
+        }
+        let accepted = (deposit as u128)
+            .checked_mul(self.fair_launch_sol_cap as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / self.total_deposits as u128;
+        Ok(accepted as u64)
+    }
+
+    /// Cap the fair launch at what the unsold tokens are worth and reserve the tokens for the
+    /// deposits accepted under it, returning the accepted lamports
+    pub fn size_fair_launch(&mut self) -> Result<u64> {
+        let tokens_available = self.total_tokens_for_sale - self.tokens_committed();
+        // Rounded down so that the tokens bought with the cap never exceed what is available
+        let sol_cap = (tokens_available as u128)
+            .checked_mul(self.token_price as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / token_unit(self.token_decimals)?;
+        let sol_cap = u64::try_from(sol_cap).unwrap_or(u64::MAX);
+        let accepted_sol = sol_cap.min(self.total_deposits);
+
+        self.fair_launch_sol_cap = sol_cap;
+        self.fair_launch_reserved = quote_tokens_for_sol(accepted_sol, self.token_price, self.token_decimals)?;
+        Ok(accepted_sol)
+    }
+
+    /// Part of `total` unlocked at `now`: the TGE share, then the rest linearly over `vesting_duration`
+    pub fn unlocked_amount(&self, total: u64, now: i64) -> u64 {
+        if self.tge_time == 0 || now < self.tge_time {
+            return 0;
+        }
+        let elapsed = now - self.tge_time;
+        if elapsed >= self.vesting_duration {
+            return total;
+        }
+        let at_tge = total as u128 * self.tge_unlock_bps as u128 / BPS_DENOMINATOR as u128;
+        let vested = (total as u128 - at_tge) * elapsed as u128 / self.vesting_duration as u128;
+        (at_tge + vested) as u64
+    }
+
+    /// Lamports still accepted under the hard cap, SPL purchases counted at their SOL value
+    pub fn hard_cap_remaining(&self) -> u64 {
+        self.hard_cap_lamports.saturating_sub(self.sol_raised + self.payment_sol_value)
+    }
+
+    /// Tokens taken out of `total_tokens_for_sale`: sold, plus referral bonuses earned on them
+    pub fn tokens_committed(&self) -> u64 {
+        self.tokens_sold + self.referral_rewards_owed
+    }
+
+    /// Flip to `SoldOut` once either the SOL hard cap or the token allocation is exhausted
+    pub fn update_sold_out(&mut self, now: i64) {
+        if self.status == PresaleStatus::Active
+            && (self.hard_cap_remaining() == 0 || self.tokens_committed() >= self.total_tokens_for_sale)
+        {
+            self.status = PresaleStatus::SoldOut;
+            emit!(PresaleSoldOut {
+                tokens_sold: self.tokens_sold,
+                sol_raised: self.sol_raised,
+                timestamp: now,
+            });
+        }
+    }
+
+    /// Sale-wide price at `now`: the decaying auction price, or the fixed `token_price`
+    pub fn current_price(&self, now: i64) -> u64 {
+        if !self.dutch_auction || now <= self.start_time {
+            return if self.dutch_auction { self.auction_start_price } else { self.token_price };
+        }
+        if now >= self.end_time {
+            return self.auction_floor_price;
+        }
+
+        let duration = self.end_time - self.start_time;
+        let mut elapsed = now - self.start_time;
+        // Stepwise decay only drops at whole step boundaries
+        if self.auction_step_interval > 0 {
+            elapsed -= elapsed % self.auction_step_interval;
+        }
+        let price_drop = (self.auction_start_price - self.auction_floor_price) as u128
+            * elapsed as u128
+            / duration as u128;
+        self.auction_start_price - price_drop as u64
+    }
+
+    /// Split `sol_amount` across rounds, starting with the first open round.
+    ///
+    /// When the active round's cap is hit the remainder either spills into the
+    /// next open round or the purchase is rejected, per `split_across_rounds`.
+    pub fn plan_purchase(&self, now: i64, sol_amount: u64) -> Result<Vec<PurchaseFill>> {
+        if self.rounds.is_empty() {
+            let token_price = self.current_price(now);
+            let token_amount = quote_tokens_for_sol(sol_amount, token_price, self.token_decimals)?;
+            return Ok(vec![PurchaseFill { round: None, token_price, token_amount, sol_amount }]);
+        }
+
+        let mut index = self
+            .rounds
+            .iter()
+            .position(|round| round.is_open(now))
+            .ok_or(ErrorCode::NoActiveRound)?;
+        let mut sol_left = sol_amount;
+        let mut fills: Vec<PurchaseFill> = Vec::new();
+
+        loop {
+            let round = &self.rounds[index];
+            let available = round.token_cap - round.tokens_sold;
+            let token_amount = quote_tokens_for_sol(sol_left, round.token_price, self.token_decimals)?;
+
+            if token_amount <= available {
+                match fills.last_mut() {
+                    // Change too small to buy anything here stays with the previous round
+                    Some(last) if token_amount == 0 => last.sol_amount += sol_left,
+                    _ => fills.push(PurchaseFill {
+                        round: Some(index),
+                        token_price: round.token_price,
+                        token_amount,
+                        sol_amount: sol_left,
+                    }),
+                }
+                return Ok(fills);
+            }
+
+            let next = self
+                .rounds
+                .iter()
+                .enumerate()
+                .skip(index + 1)
+                .find(|(_, round)| round.is_open(now))
+                .map(|(next, _)| next);
+            let next = match next {
+                Some(next) if self.split_across_rounds => next,
+                _ => return err!(ErrorCode::RoundCapExceeded),
+            };
+
+            // Fill the rest of this round at its price and carry the change over
+            let cost = quote_sol_for_tokens(available, round.token_price, self.token_decimals)?;
+            fills.push(PurchaseFill {
+                round: Some(index),
+                token_price: round.token_price,
+                token_amount: available,
+                sol_amount: cost,
+            });
+            sol_left -= cost;
+            index = next;
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PurchaseRecord {
+    pub buyer: Pubkey,
+    pub total_purchased: u64,
+    pub total_sol_spent: u64,
+    pub first_purchase_at: i64,
+    pub last_purchase_at: i64,
+    pub round_purchased: [u64; MAX_ROUNDS],
+    pub token_spent: [u64; MAX_PAYMENT_MINTS], // Indexed like `PresaleInfo::payment_mints`
+    pub refunded: bool,
+    pub tokens_claimed: u64, // Delivered to the buyer so far
+    pub deposited: u64, // Fair launch SOL deposit
+    pub deposit_settled: bool,
+    pub auction_refunded: bool,
+    pub round_released: [u64; MAX_ROUNDS], // Vested round purchases released so far
+    pub round_allocation: [u64; MAX_ROUNDS], // Personal cap proven for whitelisted rounds, 0 = not proven
+    pub referrer: Pubkey, // Default = no referrer
+}
+
+/// Per-referrer totals, one PDA per referrer so leaderboards can be read on-chain
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralRecord {
+    pub referrer: Pubkey,
+    pub total_referrals: u64, // Buyers whose first purchase named this referrer
+    pub total_referred_tokens: u64,
+    pub total_referred_sol: u64, // Lamports
+    pub rewards_earned: u64, // Bonus CAT
+    pub rewards_claimed: u64,
+}
+
+impl PurchaseRecord {
+    /// Tokens bought in rounds that vest
+    pub fn vested_purchased(&self, rounds: &[PresaleRound]) -> u64 {
+        rounds
+            .iter()
+            .zip(self.round_purchased.iter())
+            .filter(|(round, _)| round.is_vested())
+            .map(|(_, purchased)| purchased)
+            .sum()
+    }
+
+    /// Tokens bought in whitelisted rounds, which do not count towards the public `max_purchase`
+    pub fn whitelisted_purchased(&self, rounds: &[PresaleRound]) -> u64 {
+        rounds
+            .iter()
+            .zip(self.round_purchased.iter())
+            .filter(|(round, _)| round.is_whitelisted())
+            .map(|(_, purchased)| purchased)
+            .sum()
+    }
+
+    pub fn vested_released(&self) -> u64 {
+        self.round_released.iter().sum()
+    }
+}
+
+#[event]
+pub struct PresaleInitialized {
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_price: u64,
+    pub total_tokens_for_sale: u64,
+    pub min_purchase: u64,
+    pub max_purchase: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub soft_cap: u64,
+    pub hard_cap_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensPurchased {
+    pub buyer: Pubkey,
+    pub payment_mint: Option<Pubkey>, // None = paid in SOL
+    pub payment_amount: u64,
+    pub excess_not_taken: u64, // Lamports above the hard cap left with the buyer
+    pub token_amount: u64,
+    pub tokens_delivered: u64, // Transferred right away, the rest is credited or vesting
+    pub auction_price: Option<u64>,
+    pub tokens_sold: u64,
+    pub sol_raised: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundPurchased {
+    pub round_index: u8,
+    pub buyer: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub token_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleSoldOut {
+    pub tokens_sold: u64,
+    pub sol_raised: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralCredited {
+    pub referrer: Pubkey,
+    pub buyer: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub reward: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferrerRegistered {
+    pub referrer: Pubkey,
+    pub referral_record: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimed {
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub rewards_claimed: u64,
+    pub rewards_earned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralBpsUpdated {
+    pub referral_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SolDeposited {
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub deposited: u64,
+    pub total_deposits: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DepositSettled {
+    pub depositor: Pubkey,
+    pub sol_accepted: u64,
+    pub sol_refunded: u64,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundAdded {
+    pub round_index: u8,
+    pub token_price: u64,
+    pub token_cap: u64,
+    pub min_purchase: u64,
+    pub max_purchase: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundVestingSet {
+    pub round_index: u8,
+    pub tge_unlock_bps: u16,
+    pub vesting_cliff: i64,
+    pub vesting_duration: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundWhitelistSet {
+    pub round_index: u8,
+    pub merkle_root: [u8; 32],
+    pub whitelisted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DutchAuctionConfigured {
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub step_interval: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub clearing_price: u64,
+    pub sol_raised: u64,
+    pub refunds_reserved: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionRefundClaimed {
+    pub buyer: Pubkey,
+    pub clearing_price: u64,
+    pub sol_refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleFunded {
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleUpdateQueued {
+    pub update: PresaleUpdate,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleUpdated {
+    pub update: PresaleUpdate,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleUpdateCancelled {
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresalePaused {
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub config: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentMintAdded {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub token_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PayeesUpdated {
+    pub payees: Vec<PayeeShare>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PayeePaid {
+    pub wallet: Pubkey,
+    pub amount: u64,
+    pub total_paid: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProceedsDistributed {
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TgeScheduled {
+    pub tge_time: i64,
+    pub tge_unlock_bps: u16,
+    pub vesting_duration: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PurchasedTokensClaimed {
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub tokens_claimed: u64,
+    pub total_purchased: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestedTokensReleased {
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub vested_released: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleFinalized {
+    pub status: PresaleStatus,
+    pub sol_raised: u64, // Including SPL purchases at their SOL value and fair launch deposits accepted under the cap
+    pub soft_cap: u64,
+    pub fair_launch_deposits: u64,
+    pub fair_launch_sol_cap: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub buyer: Pubkey,
+    pub tokens_returned: u64,
+    pub sol_refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentTokensRefunded {
+    pub buyer: Pubkey,
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SolWithdrawn {
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentTokensWithdrawn {
+    pub authority: Pubkey,
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RemainingTokensWithdrawn {
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Presale is not active")]
+    PresaleNotActive,
+    #[msg("Presale has not started yet")]
+    PresaleNotStarted,
+    #[msg("Presale has ended")]
+    PresaleEnded,
+    #[msg("Purchase amount is below minimum")]
+    BelowMinimumPurchase,
+    #[msg("Purchase amount exceeds maximum allowed")]
+    ExceedsMaximumPurchase,
+    #[msg("Insufficient tokens available for sale")]
+    InsufficientTokensForSale,
+    #[msg("Insufficient SOL balance")]
+    InsufficientSOL,
+    #[msg("Insufficient SOL in vault")]
+    InsufficientSOLInVault,
+    #[msg("Presale is still active")]
+    PresaleStillActive,
+    #[msg("Token price must be greater than zero")]
+    InvalidTokenPrice,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("No presale round is currently open")]
+    NoActiveRound,
+    #[msg("Purchase exceeds the remaining allocation of the current round")]
+    RoundCapExceeded,
+    #[msg("Maximum number of presale rounds reached")]
+    TooManyRounds,
+    #[msg("Invalid presale round parameters")]
+    InvalidRound,
+    #[msg("Payment mint is not accepted")]
+    PaymentMintNotAccepted,
+    #[msg("Payment mint is already accepted")]
+    PaymentMintAlreadyAccepted,
+    #[msg("Maximum number of payment mints reached")]
+    TooManyPaymentMints,
+    #[msg("Insufficient payment tokens in vault")]
+    InsufficientPaymentTokensInVault,
+    #[msg("Presale has already been finalized")]
+    PresaleAlreadyFinalized,
+    #[msg("Presale did not fail")]
+    PresaleNotFailed,
+    #[msg("Presale has not succeeded")]
+    PresaleNotSucceeded,
+    #[msg("Purchase has already been refunded")]
+    AlreadyRefunded,
+    #[msg("Missing or mismatched payment refund accounts")]
+    MissingRefundAccounts,
+    #[msg("Hard cap must not be below the soft cap")]
+    InvalidCaps,
+    #[msg("Sale has already started")]
+    SaleAlreadyStarted,
+    #[msg("Deferred delivery is not enabled")]
+    DeferredDeliveryDisabled,
+    // Retired: `set_tge` now closes at `start_time` (`SaleAlreadyStarted`), kept so later codes stay put
+    #[msg("TGE has already passed")]
+    TgeAlreadyPassed,
+    #[msg("Invalid TGE schedule")]
+    InvalidTgeSchedule,
+    #[msg("TGE has not been reached")]
+    TgeNotReached,
+    #[msg("No tokens to claim")]
+    NothingToClaim,
+    #[msg("Fewer tokens than min_tokens_out would be filled")]
+    SlippageExceeded,
+    #[msg("Token price is above max_price")]
+    PriceAboveMaximum,
+    #[msg("Fair launch mode is not enabled")]
+    FairLaunchDisabled,
+    #[msg("Fair launch accepts deposits only")]
+    FairLaunchDepositsOnly,
+    #[msg("No deposit to settle")]
+    NothingToSettle,
+    #[msg("Dutch auction mode is not enabled")]
+    DutchAuctionDisabled,
+    #[msg("Dutch auction accepts SOL only")]
+    DutchAuctionSolOnly,
+    #[msg("Dutch auction cannot be combined with rounds")]
+    DutchAuctionNoRounds,
+    #[msg("Dutch auction cannot be combined with fair launch")]
+    DutchAuctionNoFairLaunch,
+    #[msg("Invalid auction configuration")]
+    InvalidAuctionConfig,
+    #[msg("Auction has not been settled")]
+    AuctionNotSettled,
+    #[msg("Auction has already been settled")]
+    AuctionAlreadySettled,
+    #[msg("Neither deferred delivery nor vested rounds are enabled")]
+    TgeNotUsed,
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+    #[msg("Token vault does not cover unreleased obligations")]
+    VaultBelowObligations,
+    #[msg("Wallet is not whitelisted for this round")]
+    NotWhitelisted,
+    #[msg("Payee shares must be unique wallets adding up to 100%")]
+    InvalidPayees,
+    #[msg("Payee accounts missing or out of order")]
+    MissingPayeeAccounts,
+    #[msg("No SOL to distribute")]
+    NothingToDistribute,
+    #[msg("Invalid referral bonus")]
+    InvalidReferralBps,
+    #[msg("Referrer must have made a purchase")]
+    ReferrerHasNoPurchase,
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+    #[msg("New authority must differ from the current one")]
+    InvalidNewAuthority,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    #[msg("An update is already queued")]
+    UpdateAlreadyQueued,
+    #[msg("No update is queued")]
+    NoUpdateQueued,
+    #[msg("Timelock delay has not passed")]
+    TimelockNotExpired,
+    #[msg("Invalid timelock delay")]
+    InvalidTimelockDelay,
+    #[msg("Payees are set, SOL is paid out through distribute_proceeds")]
+    ProceedsGoToPayees,
+    #[msg("Sales with rounds only accept SOL")]
+    RoundsSolOnly,
+    #[msg("Purchase exceeds the hard cap")]
+    HardCapExceeded,
+    #[msg("TGE has not been scheduled")]
+    TgeNotScheduled,
+    #[msg("Presale has not been finalized")]
+    PresaleNotFinalized,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECIMALS: u8 = 6;
+    const CAT: u64 = 1_000_000; // Base units per whole CAT
+
+    fn presale(rounds: Vec<PresaleRound>) -> PresaleInfo {
+        PresaleInfo {
+            token_decimals: DECIMALS,
+            token_price: 400,
+            split_across_rounds: true,
+            rounds,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_price_gives_2_5m_cat_per_sol() {
+        assert_eq!(quote_tokens_for_sol(1_000_000_000, 400, DECIMALS).unwrap(), 2_500_000 * CAT);
+        assert_eq!(quote_sol_for_tokens(2_500_000 * CAT, 400, DECIMALS).unwrap(), 1_000_000_000);
+    }
+
+    #[test]
+    fn tokens_round_down_and_sol_rounds_up() {
+        // 1 lamport at 3 lamports per CAT is 333,333.33 base units
+        assert_eq!(quote_tokens_for_sol(1, 3, DECIMALS).unwrap(), 333_333);
+        // ... and 333,333 base units cost 0.999999 lamports
+        assert_eq!(quote_sol_for_tokens(333_333, 3, DECIMALS).unwrap(), 1);
+        // A single base unit is never free
+        assert_eq!(quote_sol_for_tokens(1, 400, DECIMALS).unwrap(), 1);
+        // Too little SOL buys nothing
+        assert_eq!(quote_tokens_for_sol(399, 400_000_000, DECIMALS).unwrap(), 0);
+    }
+
+    #[test]
+    fn quotes_never_favour_the_buyer() {
+        for token_price in [1, 3, 7, 400, 999, 123_456_789] {
+            for sol_amount in [0, 1, 2, 399, 400, 401, 1_000_000_007, 123_456_789_012] {
+                let tokens = quote_tokens_for_sol(sol_amount, token_price, DECIMALS).unwrap();
+                assert!(quote_sol_for_tokens(tokens, token_price, DECIMALS).unwrap() <= sol_amount);
+            }
+            for token_amount in [0, 1, 2, 999_999, CAT, 7 * CAT + 3] {
+                let sol_amount = quote_sol_for_tokens(token_amount, token_price, DECIMALS).unwrap();
+                assert!(quote_tokens_for_sol(sol_amount, token_price, DECIMALS).unwrap() >= token_amount);
+            }
+        }
+    }
+
+    #[test]
+    fn quote_errors() {
+        assert_eq!(
+            quote_tokens_for_sol(1, 0, DECIMALS).unwrap_err(),
+            ErrorCode::InvalidTokenPrice.into()
+        );
+        assert_eq!(
+            quote_tokens_for_sol(u64::MAX, 1, DECIMALS).unwrap_err(),
+            ErrorCode::MathOverflow.into()
+        );
+        assert_eq!(
+            quote_sol_for_tokens(1, 400, 40).unwrap_err(),
+            ErrorCode::MathOverflow.into()
+        );
+    }
+
+    /// Settle every deposit the way `finalize_presale` and `settle_deposit` do
+    fn settle_fair_launch(deposits: &[u64], tokens_available: u64) -> (PresaleInfo, u64, u64) {
+        let mut presale_info = presale(Vec::new());
+        presale_info.total_tokens_for_sale = tokens_available;
+        presale_info.total_deposits = deposits.iter().sum();
+        presale_info.size_fair_launch().unwrap();
+        let reserved = presale_info.fair_launch_reserved;
+
+        let mut accepted_sol = 0;
+        let mut tokens = 0;
+        for &deposit in deposits {
+            let accepted = presale_info.fair_launch_allocation(deposit).unwrap();
+            assert!(accepted <= deposit);
+            accepted_sol += accepted;
+            tokens += quote_tokens_for_sol(accepted, 400, DECIMALS).unwrap();
+        }
+        assert!(tokens <= reserved);
+        (presale_info, accepted_sol, tokens)
+    }
+
+    #[test]
+    fn undersubscribed_fair_launch_accepts_every_deposit() {
+        let (_, accepted_sol, _) = settle_fair_launch(&[1, 400, 1_000_000_000], 10_000_000 * CAT);
+        assert_eq!(accepted_sol, 1_000_000_401);
+    }
+
+    #[test]
+    fn fair_launch_allocation_never_exceeds_the_cap() {
+        // One whale next to many dust deposits, neither divides the cap evenly
+        let mut deposits = vec![1; 997];
+        deposits.push(999_999_999_989);
+        deposits.extend([3, 7, 401, 12_345_678_901]);
+        let (presale_info, accepted_sol, tokens) = settle_fair_launch(&deposits, 1_234_567 * CAT);
+        assert!(accepted_sol <= presale_info.fair_launch_sol_cap);
+        assert!(tokens <= 1_234_567 * CAT);
+
+        // Pseudo-random skewed deposits, up to 8 orders of magnitude apart
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        for run in 0..50 {
+            let deposits: Vec<u64> = (0..200)
+                .map(|_| {
+                    seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+                    (seed >> 40) % 10u64.pow((seed % 9) as u32 + 1) + 1
+                })
+                .collect();
+            let tokens_available = (run + 1) * 7 * CAT + run;
+            let (presale_info, accepted_sol, tokens) = settle_fair_launch(&deposits, tokens_available);
+            assert!(accepted_sol <= presale_info.fair_launch_sol_cap);
+            assert!(tokens <= tokens_available);
+        }
+    }
+
+    #[test]
+    fn referral_bonuses_count_against_the_allocation() {
+        let mut presale_info = PresaleInfo {
+            status: PresaleStatus::Active,
+            hard_cap_lamports: u64::MAX,
+            total_tokens_for_sale: 1_000 * CAT,
+            tokens_sold: 950 * CAT,
+            referral_rewards_owed: 49 * CAT,
+            ..presale(vec![])
+        };
+        presale_info.update_sold_out(0);
+        assert!(presale_info.status == PresaleStatus::Active);
+
+        presale_info.referral_rewards_owed += CAT;
+        presale_info.update_sold_out(0);
+        assert!(presale_info.status == PresaleStatus::SoldOut);
+    }
+
+    fn round(token_price: u64, token_cap: u64, tokens_sold: u64) -> PresaleRound {
+        PresaleRound {
+            token_price,
+            token_cap,
+            tokens_sold,
+            sol_raised: 0,
+            min_purchase: 0,
+            max_purchase: u64::MAX,
+            start_time: 0,
+            end_time: 100,
+            tge_unlock_bps: BPS_DENOMINATOR,
+            vesting_cliff: 0,
+            vesting_duration: 0,
+            merkle_root: [0; 32],
+        }
+    }
+
+    #[test]
+    fn purchase_filling_a_round_exactly_stays_in_it() {
+        let presale_info = presale(vec![round(400, 10 * CAT, 0), round(800, 10 * CAT, 0)]);
+        let fills = presale_info.plan_purchase(50, 4_000).unwrap();
+
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].round, Some(0));
+        assert_eq!(fills[0].token_amount, 10 * CAT);
+        assert_eq!(fills[0].sol_amount, 4_000);
+    }
+
+    #[test]
+    fn purchase_spills_into_the_next_round_at_its_price() {
+        // 3 base units left in round 0, their cost rounds up to 1 lamport
+        let presale_info = presale(vec![round(400, 10 * CAT, 10 * CAT - 3), round(800, 10 * CAT, 0)]);
+        let fills = presale_info.plan_purchase(50, 801).unwrap();
+
+        assert_eq!(fills.len(), 2);
+        assert_eq!((fills[0].round, fills[0].token_amount, fills[0].sol_amount), (Some(0), 3, 1));
+        assert_eq!((fills[1].round, fills[1].token_amount, fills[1].sol_amount), (Some(1), CAT, 800));
+        assert_eq!(fills.iter().map(|fill| fill.sol_amount).sum::<u64>(), 801);
+    }
+
+    #[test]
+    fn change_too_small_for_the_next_round_stays_with_the_previous_one() {
+        let presale_info = presale(vec![round(400, CAT, 0), round(400_000_000, 10 * CAT, 0)]);
+        let fills = presale_info.plan_purchase(50, 400 + 399).unwrap();
+
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].round, Some(0));
+        assert_eq!(fills[0].token_amount, CAT);
+        assert_eq!(fills[0].sol_amount, 799);
+    }
+
+    #[test]
+    fn purchase_over_the_round_cap_is_rejected_without_splitting() {
+        let mut presale_info = presale(vec![round(400, CAT, 0), round(800, CAT, 0)]);
+        presale_info.split_across_rounds = false;
+
+        assert_eq!(
+            presale_info.plan_purchase(50, 401).err().unwrap(),
+            ErrorCode::RoundCapExceeded.into()
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Fair launch settlement against an in-process validator (`solana-program-test`).
+//!
+//! Run with `cargo test -p cat-presale --test fair_launch`.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::TransactionError;
+
+use cat_presale::{accounts, instruction as args, ErrorCode, PresaleInfo, PresaleStatus, PresaleUpdate, ID};
+
+const CAT: u64 = 1_000_000; // Base units per whole CAT
+const DAY: i64 = 24 * 60 * 60;
+
+/// `processor!` wants a plain `fn`, Anchor's `entry` ties the account slice to `'info`
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    cat_presale::entry(program_id, accounts, data)
+}
+
+fn instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+fn pda(seed: &[u8]) -> Pubkey {
+    Pubkey::find_program_address(&[seed], &ID).0
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> std::result::Result<(), BanksClientError> {
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = solana_sdk::transaction::Transaction::new_signed_with_payer(
+        instructions,
+        Some(&signers[0].pubkey()),
+        signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn account<T: AccountDeserialize>(context: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+async fn token_balance(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+fn assert_error(result: std::result::Result<(), BanksClientError>, error: ErrorCode) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, u32::from(error))
+        }
+        other => panic!("expected {error:?}, got {other:?}"),
+    }
+}
+
+/// Move the clock to `unix_timestamp` on a fresh slot, so that repeated instructions get a new blockhash
+async fn warp_to(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    context.warp_to_slot(clock.slot + 2).unwrap();
+    clock.slot += 2;
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+/// Create a token account for `mint` owned by `owner`
+async fn token_account(context: &mut ProgramTestContext, mint: Pubkey, owner: Pubkey) -> Pubkey {
+    let account = Keypair::new();
+    let payer = context.payer.insecure_clone();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let create = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_account(&spl_token::ID, &account.pubkey(), &mint, &owner).unwrap(),
+    ];
+    send(context, &create, &[&payer, &account]).await.unwrap();
+    account.pubkey()
+}
+
+#[tokio::test]
+async fn withdrawal_waits_for_finalization_and_leaves_deposits_settleable() {
+    let program = ProgramTest::new("cat_presale", ID, processor!(process_instruction));
+    let mut context = program.start_with_context().await;
+    let authority = context.payer.insecure_clone();
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    let start_time = now + 2 * DAY;
+    let end_time = start_time + DAY;
+
+    // 1,000 CAT for sale at 400 lamports each caps the fair launch at 400,000 lamports.
+    // The vault holds 200 CAT more than that.
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let create_mint = [
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &authority.pubkey(), None, 6)
+            .unwrap(),
+    ];
+    send(&mut context, &create_mint, &[&authority, &mint]).await.unwrap();
+
+    let presale_info = pda(b"presale_info");
+    let token_vault = pda(b"token_vault");
+    let initialize = instruction(
+        accounts::InitializePresale {
+            authority: authority.pubkey(),
+            presale_info,
+            token_mint: mint.pubkey(),
+            token_vault,
+            sol_vault: pda(b"sol_vault"),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        args::InitializePresale {
+            token_price: 400,
+            total_tokens_for_sale: 1_000 * CAT,
+            min_purchase: 0,
+            max_purchase: u64::MAX,
+            start_time,
+            end_time,
+            soft_cap: 0,
+            hard_cap_lamports: u64::MAX,
+        },
+    );
+    let fund_vault = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &mint.pubkey(),
+        &token_vault,
+        &authority.pubkey(),
+        &[],
+        1_200 * CAT,
+    )
+    .unwrap();
+    let queue = |update: PresaleUpdate| {
+        instruction(
+            accounts::UpdatePresale { authority: authority.pubkey(), presale_info },
+            args::QueuePresaleUpdate { update },
+        )
+    };
+    let enable_fair_launch = queue(PresaleUpdate {
+        new_token_price: None,
+        new_end_time: None,
+        new_is_active: None,
+        new_min_purchase: None,
+        new_max_purchase: None,
+        new_split_across_rounds: None,
+        new_deferred_delivery: None,
+        new_fair_launch: Some(true),
+        new_timelock_delay: None,
+    });
+    send(&mut context, &[initialize, fund_vault, enable_fair_launch], &[&authority]).await.unwrap();
+    warp_to(&mut context, now + DAY + 1).await;
+    let execute = instruction(
+        accounts::UpdatePresale { authority: authority.pubkey(), presale_info },
+        args::ExecutePresaleUpdate {},
+    );
+    send(&mut context, &[execute], &[&authority]).await.unwrap();
+
+    // 800,000 lamports deposited against the 400,000 cap
+    warp_to(&mut context, start_time + 1).await;
+    let depositors: Vec<Keypair> = (0..2).map(|_| Keypair::new()).collect();
+    for (depositor, amount) in depositors.iter().zip([300_000, 500_000]) {
+        let fund = system_instruction::transfer(&authority.pubkey(), &depositor.pubkey(), 1_000_000_000);
+        send(&mut context, &[fund], &[&authority]).await.unwrap();
+        let deposit = instruction(
+            accounts::DepositSol {
+                buyer: depositor.pubkey(),
+                presale_info,
+                purchase_record: Pubkey::find_program_address(
+                    &[b"purchase_record", depositor.pubkey().as_ref()],
+                    &ID,
+                )
+                .0,
+                sol_vault: pda(b"sol_vault"),
+                system_program: system_program::ID,
+            },
+            args::DepositSol { amount },
+        );
+        send(&mut context, &[deposit], &[depositor]).await.unwrap();
+    }
+
+    // The window has closed but the deposits are not sized yet, so the vault stays put
+    warp_to(&mut context, end_time + 1).await;
+    let authority_tokens = token_account(&mut context, mint.pubkey(), authority.pubkey()).await;
+    let withdraw = instruction(
+        accounts::WithdrawRemainingTokens {
+            authority: authority.pubkey(),
+            presale_info,
+            authority_token_account: authority_tokens,
+            token_vault,
+            token_program: spl_token::ID,
+        },
+        args::WithdrawRemainingTokens {},
+    );
+    let result = send(&mut context, std::slice::from_ref(&withdraw), &[&authority]).await;
+    assert_error(result, ErrorCode::PresaleNotFinalized);
+    assert_eq!(token_balance(&mut context, token_vault).await, 1_200 * CAT);
+
+    let finalize = instruction(accounts::FinalizePresale { presale_info }, args::FinalizePresale {});
+    send(&mut context, &[finalize], &[&authority]).await.unwrap();
+    let presale = account::<PresaleInfo>(&mut context, presale_info).await;
+    assert!(presale.status == PresaleStatus::Succeeded);
+    assert_eq!(presale.fair_launch_reserved, 1_000 * CAT);
+
+    // Only the surplus above the reserve leaves the vault
+    // Same transaction as the rejected withdraw, so it needs a new blockhash to be processed again
+    warp_to(&mut context, end_time + 2).await;
+    context.get_new_latest_blockhash().await.unwrap();
+    send(&mut context, &[withdraw], &[&authority]).await.unwrap();
+    assert_eq!(token_balance(&mut context, authority_tokens).await, 200 * CAT);
+    assert_eq!(token_balance(&mut context, token_vault).await, 1_000 * CAT);
+
+    // Every depositor still gets their pro-rata allocation and the unused SOL back
+    for (depositor, expected) in depositors.iter().zip([375 * CAT, 625 * CAT]) {
+        let depositor_tokens = token_account(&mut context, mint.pubkey(), depositor.pubkey()).await;
+        let balance_before = context.banks_client.get_balance(depositor.pubkey()).await.unwrap();
+        let settle = instruction(
+            accounts::SettleDeposit {
+                payer: authority.pubkey(),
+                depositor: depositor.pubkey(),
+                presale_info,
+                purchase_record: Pubkey::find_program_address(
+                    &[b"purchase_record", depositor.pubkey().as_ref()],
+                    &ID,
+                )
+                .0,
+                token_vault,
+                sol_vault: pda(b"sol_vault"),
+                depositor_token_account: depositor_tokens,
+                token_program: spl_token::ID,
+            },
+            args::SettleDeposit {},
+        );
+        send(&mut context, &[settle], &[&authority]).await.unwrap();
+        assert_eq!(token_balance(&mut context, depositor_tokens).await, expected);
+        // Half of each deposit fitted under the cap, the other half is refunded
+        let refund = context.banks_client.get_balance(depositor.pubkey()).await.unwrap() - balance_before;
+        assert_eq!(refund, expected * 400 / CAT);
+    }
+    assert_eq!(token_balance(&mut context, token_vault).await, 0);
+}
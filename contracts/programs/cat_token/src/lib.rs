@@ -0,0 +1,410 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("GN5JjprT9NSAmmB2WW7AHi8zQVMBVeEYqF2wkg4wMTBC");
+
+#[program]
+pub mod cat_token {
+    use super::*;
+
+    /// Initialize the CAT COIN token mint
+    pub fn initialize_token(
+        ctx: Context<InitializeToken>,
+        decimals: u8,
+        name: String,
+        symbol: String,
+    ) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.authority = ctx.accounts.authority.key();
+        token_info.mint = ctx.accounts.mint.key();
+        token_info.name = name;
+        token_info.symbol = symbol;
+        token_info.decimals = decimals;
+        token_info.total_supply = 0;
+        token_info.max_supply = 1_000_000_000_000_000; // 1 Quadrillion CAT tokens (with 6 decimals)
+        token_info.pending_authority = Pubkey::default();
+        
+        emit!(TokenInitialized {
+            authority: token_info.authority,
+            mint: token_info.mint,
+            name: token_info.name.clone(),
+            symbol: token_info.symbol.clone(),
+            decimals: token_info.decimals,
+            max_supply: token_info.max_supply,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Mint new CAT tokens (only by authority)
+    pub fn mint_tokens(
+        ctx: Context<MintTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        
+        // Check if minting would exceed max supply
+        require!(
+            token_info.total_supply + amount <= token_info.max_supply,
+            ErrorCode::ExceedsMaxSupply
+        );
+
+        // Mint tokens, `token_info` is the SPL mint authority
+        let seeds = &[
+            b"token_info".as_ref(),
+            &[ctx.bumps.token_info],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = token::MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: token_info.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        
+        token::mint_to(cpi_ctx, amount)?;
+        
+        // Update total supply
+        token_info.total_supply += amount;
+        
+        emit!(TokensMinted {
+            mint: token_info.mint,
+            to: ctx.accounts.to.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            total_supply: token_info.total_supply,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Transfer CAT tokens between accounts
+    pub fn transfer_tokens(
+        ctx: Context<TransferTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        
+        token::transfer(cpi_ctx, amount)?;
+        
+        emit!(TokensTransferred {
+            from: ctx.accounts.from.key(),
+            to: ctx.accounts.to.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Burn CAT tokens
+    pub fn burn_tokens(
+        ctx: Context<BurnTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        
+        let cpi_accounts = token::Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.from.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        
+        token::burn(cpi_ctx, amount)?;
+        
+        // Update total supply
+        token_info.total_supply -= amount;
+        
+        emit!(TokensBurned {
+            mint: token_info.mint,
+            from: ctx.accounts.from.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            total_supply: token_info.total_supply,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    /// Propose a new authority for `TokenInfo`; it takes over once it calls `accept_authority` (only authority).
+    /// The SPL mint authority is the `token_info` PDA, so minting rights move with it.
+    pub fn propose_authority(
+        ctx: Context<UpdateTokenAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+
+        require!(
+            new_authority != Pubkey::default() && new_authority != token_info.authority,
+            ErrorCode::InvalidNewAuthority
+        );
+        token_info.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            config: token_info.key(),
+            authority: token_info.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Complete a proposed authority transfer (only the pending authority)
+    pub fn accept_authority(
+        ctx: Context<AcceptTokenAuthority>,
+    ) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+
+        let previous_authority = token_info.authority;
+        token_info.authority = ctx.accounts.new_authority.key();
+        token_info.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferred {
+            config: token_info.key(),
+            previous_authority,
+            new_authority: token_info.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw a proposed authority transfer (only authority)
+    pub fn cancel_authority_transfer(
+        ctx: Context<UpdateTokenAuthority>,
+    ) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+
+        require!(
+            token_info.pending_authority != Pubkey::default(),
+            ErrorCode::NoPendingAuthority
+        );
+        emit!(AuthorityTransferCancelled {
+            config: token_info.key(),
+            authority: token_info.authority,
+            cancelled_authority: token_info.pending_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        token_info.pending_authority = Pubkey::default();
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeToken<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TokenInfo::INIT_SPACE,
+        seeds = [b"token_info"],
+        bump
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 6,
+        mint::authority = token_info,
+    )]
+    pub mint: Account<'info, Mint>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct MintTokens<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info"],
+        bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TransferTokens<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info"],
+        bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTokenAuthority<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info"],
+        bump,
+        has_one = authority
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptTokenAuthority<'info> {
+    pub new_authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info"],
+        bump,
+        constraint = token_info.pending_authority == new_authority.key() @ ErrorCode::NotPendingAuthority
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TokenInfo {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    #[max_len(32)]
+    pub name: String,
+    #[max_len(10)]
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: u64,
+    pub max_supply: u64,
+    pub pending_authority: Pubkey, // Default = no transfer pending
+}
+
+#[event]
+pub struct TokenInitialized {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub max_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensMinted {
+    pub mint: Pubkey,
+    pub to: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub total_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensTransferred {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub total_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub config: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Minting would exceed maximum supply")]
+    ExceedsMaxSupply,
+    #[msg("New authority must differ from the current one")]
+    InvalidNewAuthority,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+}
\ No newline at end of file